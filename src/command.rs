@@ -3,11 +3,17 @@ pub mod pipeline;
 mod receiver;
 mod stdin;
 
-pub use builder::{Command, CommandOutput, cmd, sh};
+#[cfg(unix)]
+pub use builder::sh_args;
+pub use builder::{
+    Command, CommandMetrics, CommandOutput, bridge, cmd, from_argv, run_all, sh, sh_in,
+};
 pub use pipeline::Pipeline;
 
 pub(crate) use receiver::ReceiverIter;
-pub(crate) use stdin::{StdinJoinHandle, StdinSource, feed_child_stdin, wait_stdin_writer};
+pub(crate) use stdin::{
+    LinesReader, StdinJoinHandle, StdinSource, spawn_with_stdin, wait_stdin_writer,
+};
 
 #[cfg(test)]
 mod tests;
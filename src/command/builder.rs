@@ -1,20 +1,30 @@
-use crate::{Error, Result, Shell};
+use crate::{Error, Result, Shell, quote};
 
 use std::{
-    ffi::OsString,
+    borrow::Cow,
+    ffi::{OsStr, OsString},
     fs::{self, OpenOptions},
     io::{BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
     process::{Child, Command as StdCommand, ExitStatus, Output, Stdio},
     sync::mpsc,
     thread,
+    time::{Duration, Instant},
 };
 
 #[cfg(feature = "async")]
-use tokio::{io::AsyncWriteExt, process::Command as TokioCommand, task};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    process::Command as TokioCommand,
+    task,
+};
+
+#[cfg(feature = "async")]
+use super::stdin::copy_to_sinks;
 
 use super::{
-    Pipeline, ReceiverIter, StdinJoinHandle, StdinSource, feed_child_stdin, wait_stdin_writer,
+    LinesReader, Pipeline, ReceiverIter, StdinJoinHandle, StdinSource, spawn_with_stdin,
+    wait_stdin_writer,
 };
 
 /// Alias to make builder intentions clearer in docs (`CommandBuilder` == [`Command`]).
@@ -23,15 +33,31 @@ pub type CommandBuilder = Command;
 
 /// Builder that mirrors `std::process::Command` but surfaces a friendlier API
 /// tailored for composing pipelines.
+///
+/// The child environment is resolved in a fixed order on every spawn path:
+/// the inherited environment is cleared (if requested), variables kept by
+/// [`Command::env_inherit_filter`] are added, explicit [`Command::env`] values
+/// are set, and finally [`Command::env_remove`] removals are applied.
 #[derive(Debug)]
 pub struct Command {
     pub(crate) program: OsString,
     pub(crate) args: Vec<OsString>,
-    pub(crate) env: Vec<(OsString, OsString)>,
+    pub(crate) env: Vec<(OsString, Option<OsString>)>,
+    pub(crate) inherited_env: Vec<(OsString, OsString)>,
     pub(crate) clear_env: bool,
     pub(crate) current_dir: Option<PathBuf>,
     pub(crate) stdin: Option<StdinSource>,
     pub(crate) inherit_stdin: bool,
+    pub(crate) max_output_bytes: Option<usize>,
+    pub(crate) retry: Option<RetryPolicy>,
+}
+
+/// Retry settings applied by [`Command::retry`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    retries: usize,
+    base_delay: Duration,
+    multiplier: u32,
 }
 
 impl Clone for Command {
@@ -40,10 +66,13 @@ impl Clone for Command {
             program: self.program.clone(),
             args: self.args.clone(),
             env: self.env.clone(),
+            inherited_env: self.inherited_env.clone(),
             clear_env: self.clear_env,
             current_dir: self.current_dir.clone(),
             stdin: self.stdin.as_ref().and_then(StdinSource::try_clone),
             inherit_stdin: self.inherit_stdin,
+            max_output_bytes: self.max_output_bytes,
+            retry: self.retry,
         }
     }
 }
@@ -55,19 +84,39 @@ impl Command {
             program: program.into(),
             args: Vec::new(),
             env: Vec::new(),
+            inherited_env: Vec::new(),
             clear_env: false,
             current_dir: None,
             stdin: None,
             inherit_stdin: false,
+            max_output_bytes: None,
+            retry: None,
         }
     }
 
+    /// Returns the program this command will execute.
+    pub fn get_program(&self) -> &OsStr {
+        &self.program
+    }
+
+    /// Returns the arguments passed to the program.
+    pub fn get_args(&self) -> &[OsString] {
+        &self.args
+    }
+
     /// Adds a single argument.
     pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
         self.args.push(arg.into());
         self
     }
 
+    /// Adds an argument from raw bytes, which need not be valid UTF-8.
+    #[cfg(unix)]
+    pub fn arg_bytes(self, bytes: &[u8]) -> Self {
+        use std::os::unix::ffi::OsStrExt;
+        self.arg(OsStr::from_bytes(bytes))
+    }
+
     /// Extends the command with multiple arguments.
     pub fn args<I, S>(mut self, args: I) -> Self
     where
@@ -79,17 +128,67 @@ impl Command {
     }
 
     /// Sets/overrides an environment variable.
+    ///
+    /// Setting the same key again replaces the earlier value (last write wins).
+    /// Keys compare case-sensitively, except on Windows where case is ignored.
     pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
-        self.env.push((key.into(), value.into()));
+        self.set_env_entry(key.into(), Some(value.into()));
+        self
+    }
+
+    /// Sets/overrides multiple environment variables, with the same rules as [`Command::env`].
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<OsString>,
+        V: Into<OsString>,
+    {
+        for (key, value) in vars {
+            self.set_env_entry(key.into(), Some(value.into()));
+        }
         self
     }
 
+    /// Removes an environment variable from the child, even if it is inherited.
+    ///
+    /// A later [`Command::env`] for the same key replaces the removal, and vice versa.
+    pub fn env_remove(mut self, key: impl Into<OsString>) -> Self {
+        self.set_env_entry(key.into(), None);
+        self
+    }
+
+    /// Returns the explicit environment overrides; `None` marks a removed variable.
+    ///
+    /// Variables snapshotted by [`Command::env_inherit_filter`] are not included.
+    pub fn get_envs(&self) -> impl Iterator<Item = (&OsStr, Option<&OsStr>)> {
+        self.env
+            .iter()
+            .map(|(key, value)| (key.as_os_str(), value.as_deref()))
+    }
+
     /// Clears the inherited environment before applying overrides.
     pub fn clear_env(mut self) -> Self {
         self.clear_env = true;
         self
     }
 
+    /// Clears the inherited environment, then passes through parent variables
+    /// for which `keep(key, value)` returns `true`.
+    ///
+    /// The parent environment is sampled when this method is called, replacing
+    /// any earlier snapshot. Values set via [`Command::env`] or removed via
+    /// [`Command::env_remove`] always take precedence over inherited ones.
+    pub fn env_inherit_filter<F>(mut self, keep: F) -> Self
+    where
+        F: Fn(&OsStr, &OsStr) -> bool,
+    {
+        self.clear_env = true;
+        self.inherited_env = std::env::vars_os()
+            .filter(|(key, value)| keep(key, value))
+            .collect();
+        self
+    }
+
     /// Sets the working directory.
     pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
         self.current_dir = Some(dir.into());
@@ -113,6 +212,44 @@ impl Command {
         self
     }
 
+    /// Feeds the captured stdout of `producer` into this command's stdin.
+    ///
+    /// Unlike [`Command::pipe`], this is sequential and buffered: `producer` runs
+    /// to completion before each spawn of this command. If it fails, this
+    /// command is never started and the producer's error is returned.
+    pub fn stdin_from(mut self, producer: Command) -> Self {
+        self.stdin = Some(StdinSource::Command(Box::new(producer), Default::default()));
+        self.inherit_stdin = false;
+        self
+    }
+
+    /// Lazily writes each line followed by `\n` to the command's stdin.
+    ///
+    /// Lines are pulled on a background writer thread, so the iterator must be
+    /// `Send`. Like [`Command::stdin_reader`], the source is not carried over
+    /// when the command is cloned.
+    pub fn stdin_lines<I>(self, lines: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: Send + 'static,
+    {
+        self.stdin_reader(LinesReader::new(lines.into_iter().map(Ok)))
+    }
+
+    /// Copies every byte written to the child's stdin into `sink` as well.
+    ///
+    /// Wraps the stdin source configured so far (bytes, reader or producer), so
+    /// call it after [`Command::stdin`], [`Command::stdin_reader`] or
+    /// [`Command::stdin_from`]; without a source this is a no-op. The tee moves writing onto a background thread and,
+    /// like a reader source, is not carried over when the command is cloned.
+    pub fn stdin_tee<W>(mut self, sink: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        self.stdin = self.stdin.take().map(|source| source.tee(sink));
+        self
+    }
+
     /// Makes the process inherit the parent's stdin rather than capturing it.
     pub fn inherit_stdin(mut self, inherit: bool) -> Self {
         self.inherit_stdin = inherit;
@@ -122,9 +259,130 @@ impl Command {
         self
     }
 
+    /// Caps how many bytes of stdout plus stderr may be captured.
+    ///
+    /// Once the limit is exceeded the child is killed and capturing methods such
+    /// as [`Command::output`] fail with [`Error::OutputTooLarge`]. The cap is
+    /// approximate: output is read in chunks, so the check fires after the chunk
+    /// that crosses the limit.
+    pub fn max_output_bytes(mut self, limit: usize) -> Self {
+        self.max_output_bytes = Some(limit);
+        self
+    }
+
+    /// Reruns the command up to `retries` more times when it fails to spawn or
+    /// exits non-zero, sleeping `base_delay` before the first retry and doubling
+    /// the delay after each one.
+    ///
+    /// Applies to [`Command::output`] (and the helpers built on it),
+    /// [`Command::status`], and [`Command::run`]. The last failure is returned
+    /// when every attempt fails. A [`Command::stdin_reader`] source is consumed by
+    /// the first attempt, so retries of such commands fail fast.
+    pub fn retry(mut self, retries: usize, base_delay: Duration) -> Self {
+        let multiplier = self.retry.map_or(2, |policy| policy.multiplier);
+        self.retry = Some(RetryPolicy {
+            retries,
+            base_delay,
+            multiplier,
+        });
+        self
+    }
+
+    /// Sets the backoff multiplier used by [`Command::retry`] (defaults to `2`).
+    pub fn retry_multiplier(mut self, multiplier: u32) -> Self {
+        let policy = self.retry.get_or_insert(RetryPolicy {
+            retries: 0,
+            base_delay: Duration::ZERO,
+            multiplier,
+        });
+        policy.multiplier = multiplier;
+        self
+    }
+
+    /// Renders the command as a copy-pasteable line for the platform shell.
+    ///
+    /// Nothing is executed. Working directory and environment overrides are
+    /// rendered as a `cd <dir> && KEY=value program args` prefix.
+    pub fn to_shell_string(&self) -> String {
+        let mut out = String::new();
+        if let Some(dir) = &self.current_dir {
+            out.push_str("cd ");
+            out.push_str(&shell_quote(dir.as_os_str()));
+            out.push_str(" && ");
+        }
+        let env = self.resolved_env();
+        if cfg!(windows) {
+            for (key, value) in &env {
+                let value = value.unwrap_or_default();
+                let assignment = format!("{}={}", key.to_string_lossy(), value.to_string_lossy());
+                out.push_str("set ");
+                out.push_str(&shell_quote(OsStr::new(&assignment)));
+                out.push_str(" && ");
+            }
+        } else {
+            if self.clear_env {
+                out.push_str("env -i ");
+            } else if env.iter().any(|(_, value)| value.is_none()) {
+                out.push_str("env ");
+                for (key, _) in env.iter().filter(|(_, value)| value.is_none()) {
+                    out.push_str("-u ");
+                    out.push_str(&shell_quote(key));
+                    out.push(' ');
+                }
+            }
+            for (key, value) in &env {
+                if let Some(value) = value {
+                    out.push_str(&key.to_string_lossy());
+                    out.push('=');
+                    out.push_str(&shell_quote(value));
+                    out.push(' ');
+                }
+            }
+        }
+        out.push_str(&shell_quote(&self.program));
+        for arg in &self.args {
+            out.push(' ');
+            out.push_str(&shell_quote(arg));
+        }
+        out
+    }
+
+    /// Returns whether the program can be found, without spawning it.
+    ///
+    /// Bare names are looked up on `PATH` via [`which`](crate::which); relative
+    /// paths such as `./build.sh` are resolved against the configured working
+    /// directory when one is set.
+    pub fn is_available(&self) -> bool {
+        self.resolve_program().is_some()
+    }
+
+    pub(crate) fn resolve_program(&self) -> Option<PathBuf> {
+        let program = Path::new(&self.program);
+        match &self.current_dir {
+            Some(dir) if program.is_relative() && program.components().count() > 1 => {
+                crate::which(dir.join(program))
+            }
+            _ => crate::which(program),
+        }
+    }
+
     /// Executes the command and returns its captured output.
+    ///
+    /// Without a configured stdin source the child reads from the null
+    /// device, so programs that wait on stdin see EOF instead of blocking on
+    /// the parent's terminal. Use [`Command::inherit_stdin`] to opt back in.
     pub fn output(&self) -> Result<CommandOutput> {
-        let std_output = self.spawn_and_wait()?;
+        self.retrying(
+            || self.output_once(),
+            |result| result.as_ref().is_err_and(is_retryable),
+        )
+    }
+
+    fn output_once(&self) -> Result<CommandOutput> {
+        self.checked_output(self.spawn_and_wait()?)
+    }
+
+    fn checked_output(&self, std_output: Output) -> Result<CommandOutput> {
         if !std_output.status.success() {
             return Err(Error::Command {
                 program: self.program.clone(),
@@ -139,31 +397,127 @@ impl Command {
         })
     }
 
+    /// Like [`Command::output`], but runs scripts through the interpreter named
+    /// in their shebang line, so `cmd("tool.py").run_script()` also works on
+    /// Windows.
+    ///
+    /// Applies when the program ends in a known script extension (`sh`, `bash`,
+    /// `py`, `pl`, `rb`) and starts with `#!`. Both `#!/usr/bin/env python3`
+    /// and direct paths such as `#!/bin/sh` are understood; an interpreter path
+    /// that does not exist on this system is looked up on `PATH` by its file
+    /// name. Anything else runs exactly like [`Command::output`].
+    pub fn run_script(&self) -> Result<CommandOutput> {
+        let Some((script, interpreter, interpreter_args)) = self.script_interpreter()? else {
+            return self.output();
+        };
+        self.retrying(
+            || {
+                let mut command = StdCommand::new(&interpreter);
+                command.args(&interpreter_args).arg(&script);
+                let std_output = self.wait_for_output(self.prepare_std_command(command))?;
+                self.checked_output(std_output)
+            },
+            |result| result.as_ref().is_err_and(is_retryable),
+        )
+    }
+
+    fn script_interpreter(&self) -> Result<Option<(PathBuf, OsString, Vec<String>)>> {
+        let program = Path::new(&self.program);
+        let is_script = program
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| SCRIPT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        if !is_script {
+            return Ok(None);
+        }
+        let script = match &self.current_dir {
+            Some(dir) if program.is_relative() => dir.join(program),
+            _ => program.to_path_buf(),
+        };
+        let script = if script.is_file() {
+            script
+        } else {
+            self.resolve_program().unwrap_or(script)
+        };
+        let mut first_line = String::new();
+        BufReader::new(fs::File::open(&script)?).read_line(&mut first_line)?;
+        Ok(parse_shebang(&first_line).map(|(interpreter, args)| (script, interpreter, args)))
+    }
+
+    /// Like [`Command::output`], also reporting how long the call took and how
+    /// much it captured. The duration includes any retries.
+    pub fn run_metrics(&self) -> Result<(CommandOutput, CommandMetrics)> {
+        let started = Instant::now();
+        let output = self.output()?;
+        let metrics = CommandMetrics {
+            duration: started.elapsed(),
+            stdout_bytes: output.stdout.len(),
+            stderr_bytes: output.stderr.len(),
+            exit_code: output.status.code(),
+        };
+        Ok((output, metrics))
+    }
+
+    /// Like [`Command::output`], calling `on_bytes` with the cumulative stdout
+    /// size each time another chunk arrives.
+    ///
+    /// The full output is still buffered and returned. Retry policies are not
+    /// applied, so the count never resets mid-call.
+    pub fn output_with_progress(&self, on_bytes: impl FnMut(usize)) -> Result<CommandOutput> {
+        let mut command = self.build_std_command();
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
+        let output = match capture_chunks(&mut child, self.max_output_bytes, on_bytes) {
+            Ok(output) => output,
+            Err(err) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                let _ = wait_stdin_writer(stdin_handle);
+                return Err(err);
+            }
+        };
+        wait_stdin_writer(stdin_handle)?;
+        if !output.status.success() {
+            return Err(Error::Command {
+                program: self.program.clone(),
+                status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            });
+        }
+        Ok(CommandOutput {
+            status: output.status,
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+
     /// Runs the command, ignoring stdout/stderr, returning only the exit status.
+    ///
+    /// Like [`Command::output`], stdin defaults to the null device.
     pub fn status(&self) -> Result<ExitStatus> {
-        Ok(self.spawn_and_wait()?.status)
+        self.retrying(
+            || Ok(self.spawn_and_wait()?.status),
+            |result| match result {
+                Ok(status) => !status.success(),
+                Err(err) => is_retryable(err),
+            },
+        )
     }
 
     /// Runs the command while inheriting stdout/stderr from the parent process.
     pub fn run(&self) -> Result<()> {
-        let mut command = StdCommand::new(&self.program);
-        command.args(&self.args);
-        if self.clear_env {
-            command.env_clear();
-        }
-        command.envs(self.env.iter().cloned());
-        if let Some(dir) = &self.current_dir {
-            command.current_dir(dir);
-        }
-        if self.stdin.is_some() {
-            command.stdin(Stdio::piped());
-        } else if self.inherit_stdin {
-            command.stdin(Stdio::inherit());
-        }
+        self.retrying(
+            || self.run_once(),
+            |result| result.as_ref().is_err_and(is_retryable),
+        )
+    }
+
+    fn run_once(&self) -> Result<()> {
+        let mut command = self.build_std_command();
         command.stdout(Stdio::inherit());
         command.stderr(Stdio::inherit());
-        let mut child = command.spawn()?;
-        let stdin_handle = feed_child_stdin(&mut child, &self.stdin)?;
+        let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
         let status = child.wait()?;
         wait_stdin_writer(stdin_handle)?;
         if status.success() {
@@ -182,6 +536,25 @@ impl Command {
         self.output()?.stdout_string()
     }
 
+    /// Like [`Command::stdout_text`], but stderr streams live to the parent's
+    /// stderr instead of being captured.
+    pub fn stdout_text_inherit_stderr(&self) -> Result<String> {
+        let mut command = self.build_std_command();
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::inherit());
+        let (child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
+        let output = child.wait_with_output()?;
+        wait_stdin_writer(stdin_handle)?;
+        if !output.status.success() {
+            return Err(Error::Command {
+                program: self.program.clone(),
+                status: output.status,
+                stderr: "stderr inherited by parent".into(),
+            });
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
     /// Returns stdout split by lines into a [`Shell`].
     pub fn lines(&self) -> Result<Shell<String>> {
         let text = self.stdout_text()?;
@@ -197,8 +570,7 @@ impl Command {
         let mut command = self.build_std_command();
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
-        let mut child = command.spawn()?;
-        let stdin_handle = feed_child_stdin(&mut child, &self.stdin)?;
+        let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
         let stdout = child
             .stdout
             .take()
@@ -308,27 +680,61 @@ impl Command {
                 "stdin_reader is not supported in async mode",
             )));
         }
+        let produced = match &self.stdin {
+            Some(StdinSource::Command(producer, sinks)) => {
+                let stdout = Box::pin(producer.output_async()).await?.stdout;
+                copy_to_sinks(sinks, &stdout)?;
+                Some(stdout)
+            }
+            _ => None,
+        };
         let mut command = self.build_tokio_command();
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
         let mut child = command.spawn()?;
-        if let Some(StdinSource::Bytes(input)) = &self.stdin
-            && let Some(mut stdin) = child.stdin.take()
-        {
-            stdin.write_all(input).await?;
-        }
-        let output = child.wait_with_output().await?;
-        if !output.status.success() {
+        let input = match &self.stdin {
+            Some(StdinSource::Bytes(input)) => Some(input),
+            _ => produced.as_ref(),
+        };
+        // Feed stdin while draining both pipes so a chatty child can't fill a
+        // pipe buffer and deadlock against our write.
+        let stdin = child.stdin.take();
+        let write_stdin = async move {
+            if let (Some(input), Some(mut stdin)) = (input, stdin) {
+                stdin.write_all(input).await?;
+            }
+            Ok::<_, std::io::Error>(())
+        };
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let read_stdout = async {
+            match child.stdout.as_mut() {
+                Some(pipe) => pipe.read_to_end(&mut stdout).await.map(|_| ()),
+                None => Ok(()),
+            }
+        };
+        let read_stderr = async {
+            match child.stderr.as_mut() {
+                Some(pipe) => pipe.read_to_end(&mut stderr).await.map(|_| ()),
+                None => Ok(()),
+            }
+        };
+        let (written, read_out, read_err) = tokio::join!(write_stdin, read_stdout, read_stderr);
+        read_out?;
+        read_err?;
+        let status = child.wait().await?;
+        if !status.success() {
             return Err(Error::Command {
                 program: self.program.clone(),
-                status: output.status,
-                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                status,
+                stderr: String::from_utf8_lossy(&stderr).to_string(),
             });
         }
+        written?;
         Ok(CommandOutput {
-            status: output.status,
-            stdout: output.stdout,
-            stderr: output.stderr,
+            status,
+            stdout,
+            stderr,
         })
     }
 
@@ -351,8 +757,7 @@ impl Command {
         let mut command = self.build_std_command();
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
-        let mut child = command.spawn()?;
-        let stdin_handle = feed_child_stdin(&mut child, &self.stdin)?;
+        let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
         let stdout = child
             .stdout
             .take()
@@ -431,6 +836,117 @@ impl Command {
         Ok(Shell::new(ReceiverIter::new(rx)))
     }
 
+    /// Like [`Command::stream_lines`], but gives up when no line arrives within `idle`.
+    ///
+    /// On timeout the child is killed and the stream ends with
+    /// [`Error::Timeout`]. The deadline restarts after every line.
+    pub fn stream_lines_timeout(&self, idle: Duration) -> Result<Shell<Result<String>>> {
+        let mut command = self.build_std_command();
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| Error::Io(std::io::Error::other("missing stdout pipe")))?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| Error::Io(std::io::Error::other("missing stderr pipe")))?;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                match reader.read_line(&mut line) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        let send_line = line.trim_end_matches(&['\r', '\n'][..]).to_string();
+                        if tx.send(Ok(send_line)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        let _ = tx.send(Err(err));
+                        break;
+                    }
+                }
+            }
+        });
+        let stderr_handle = thread::spawn(move || -> String {
+            let mut buf = String::new();
+            let _ = BufReader::new(stderr).read_to_string(&mut buf);
+            buf
+        });
+        Ok(Shell::new(TimeoutLinesIter {
+            child: Some(child),
+            program: self.program.clone(),
+            rx,
+            idle,
+            stdin_handle,
+            stderr_handle: Some(stderr_handle),
+        }))
+    }
+
+    /// Invokes `f` for every stdout line as it arrives and returns the exit status.
+    ///
+    /// Lines are delivered on the calling thread while stderr is inherited from
+    /// the parent. Unlike [`Command::stream_lines`], a non-zero exit is reported
+    /// through the returned status rather than as an error.
+    pub fn for_each_line<F>(&self, mut f: F) -> Result<ExitStatus>
+    where
+        F: FnMut(&str),
+    {
+        let mut command = self.build_std_command();
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::inherit());
+        let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| Error::Io(std::io::Error::other("missing stdout pipe")))?;
+        let mut reader = BufReader::new(stdout);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => f(line.trim_end_matches(&['\r', '\n'][..])),
+                Err(err) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    let _ = wait_stdin_writer(stdin_handle);
+                    return Err(Error::Io(err));
+                }
+            }
+        }
+        let status = child.wait()?;
+        wait_stdin_writer(stdin_handle)?;
+        Ok(status)
+    }
+
+    /// Echoes each stdout line to the parent's stdout as it arrives and also
+    /// returns all of them.
+    ///
+    /// Stderr is inherited, as with [`Command::run`]. A non-zero exit is
+    /// reported as [`Error::Command`] after the output has been printed.
+    pub fn run_teeing(&self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        let status = self.for_each_line(|line| {
+            println!("{line}");
+            lines.push(line.to_string());
+        })?;
+        if !status.success() {
+            return Err(Error::Command {
+                program: self.program.clone(),
+                status,
+                stderr: "stderr inherited by parent".into(),
+            });
+        }
+        Ok(lines)
+    }
+
     /// Streams stdout asynchronously by delegating to the blocking implementation.
     #[cfg(feature = "async")]
     pub async fn stream_lines_async(&self) -> Result<Shell<Result<String>>> {
@@ -448,19 +964,70 @@ impl Command {
         Ok(Shell::from_iter(lines))
     }
 
+    fn set_env_entry(&mut self, key: OsString, value: Option<OsString>) {
+        match self
+            .env
+            .iter_mut()
+            .find(|(existing, _)| env_key_eq(existing, &key))
+        {
+            Some(entry) => entry.1 = value,
+            None => self.env.push((key, value)),
+        }
+    }
+
+    fn retrying<T>(
+        &self,
+        mut attempt: impl FnMut() -> Result<T>,
+        should_retry: impl Fn(&Result<T>) -> bool,
+    ) -> Result<T> {
+        let mut result = attempt();
+        let Some(policy) = self.retry else {
+            return result;
+        };
+        let mut delay = policy.base_delay;
+        for _ in 0..policy.retries {
+            if !should_retry(&result) {
+                break;
+            }
+            thread::sleep(delay);
+            delay = delay.saturating_mul(policy.multiplier);
+            result = attempt();
+        }
+        result
+    }
+
     fn spawn_and_wait(&self) -> Result<Output> {
-        let mut command = self.build_std_command();
+        self.wait_for_output(self.build_std_command())
+    }
+
+    fn wait_for_output(&self, mut command: StdCommand) -> Result<Output> {
+        if self.stdin.is_none() && !self.inherit_stdin {
+            command.stdin(Stdio::null());
+        }
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
-        let mut child = command.spawn()?;
-        let stdin_handle = feed_child_stdin(&mut child, &self.stdin)?;
-        let output = child.wait_with_output()?;
+        let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &self.stdin)?;
+        let output = match self.max_output_bytes {
+            Some(limit) => match capture_chunks(&mut child, Some(limit), |_| {}) {
+                Ok(output) => output,
+                Err(err) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    let _ = wait_stdin_writer(stdin_handle);
+                    return Err(err);
+                }
+            },
+            None => child.wait_with_output()?,
+        };
         wait_stdin_writer(stdin_handle)?;
         Ok(output)
     }
 
     fn build_std_command(&self) -> StdCommand {
-        let mut command = StdCommand::new(&self.program);
+        self.prepare_std_command(StdCommand::new(&self.program))
+    }
+
+    fn prepare_std_command(&self, mut command: StdCommand) -> StdCommand {
         self.configure_std_command(&mut command);
         if self.stdin.is_some() {
             command.stdin(Stdio::piped());
@@ -475,30 +1042,149 @@ impl Command {
         if self.clear_env {
             command.env_clear();
         }
-        command.envs(self.env.iter().cloned());
+        for (key, value) in self.resolved_env() {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
         if let Some(dir) = &self.current_dir {
             command.current_dir(dir);
         }
     }
 
+    /// Environment entries in application order: inherited, set, then removed.
+    fn resolved_env(&self) -> Vec<(&OsStr, Option<&OsStr>)> {
+        let inherited = self
+            .inherited_env
+            .iter()
+            .filter(|(key, _)| {
+                !self
+                    .env
+                    .iter()
+                    .any(|(existing, _)| env_key_eq(existing, key))
+            })
+            .map(|(key, value)| (key.as_os_str(), Some(value.as_os_str())));
+        let set = self
+            .env
+            .iter()
+            .filter_map(|(key, value)| Some((key.as_os_str(), Some(value.as_deref()?))));
+        let removed = self
+            .env
+            .iter()
+            .filter(|(_, value)| value.is_none())
+            .map(|(key, _)| (key.as_os_str(), None));
+        inherited.chain(set).chain(removed).collect()
+    }
+
     #[cfg(feature = "async")]
     fn build_tokio_command(&self) -> TokioCommand {
-        let mut command = TokioCommand::new(&self.program);
-        command.args(&self.args);
-        if self.clear_env {
-            command.env_clear();
+        TokioCommand::from(self.build_std_command())
+    }
+}
+
+fn env_key_eq(a: &OsStr, b: &OsStr) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+fn is_retryable(err: &Error) -> bool {
+    matches!(err, Error::Command { .. } | Error::Io(_))
+}
+
+enum CaptureChunk {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+}
+
+/// Collects stdout/stderr like `wait_with_output`, bailing out once `limit` is exceeded.
+fn capture_chunks(
+    child: &mut Child,
+    limit: Option<usize>,
+    mut on_stdout: impl FnMut(usize),
+) -> Result<Output> {
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| Error::Io(std::io::Error::other("missing stdout pipe")))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| Error::Io(std::io::Error::other("missing stderr pipe")))?;
+    let (tx, rx) = mpsc::channel();
+    spawn_chunk_reader(stdout, tx.clone(), CaptureChunk::Stdout);
+    spawn_chunk_reader(stderr, tx, CaptureChunk::Stderr);
+    let mut stdout_buf = Vec::new();
+    let mut stderr_buf = Vec::new();
+    for chunk in rx {
+        match chunk? {
+            CaptureChunk::Stdout(data) => {
+                stdout_buf.extend_from_slice(&data);
+                on_stdout(stdout_buf.len());
+            }
+            CaptureChunk::Stderr(data) => stderr_buf.extend_from_slice(&data),
         }
-        command.envs(self.env.iter().cloned());
-        if let Some(dir) = &self.current_dir {
-            command.current_dir(dir);
+        if let Some(limit) = limit
+            && stdout_buf.len() + stderr_buf.len() > limit
+        {
+            return Err(Error::OutputTooLarge { limit });
         }
-        if self.stdin.is_some() {
-            command.stdin(Stdio::piped());
-        } else if self.inherit_stdin {
-            command.stdin(Stdio::inherit());
+    }
+    let status = child.wait()?;
+    Ok(Output {
+        status,
+        stdout: stdout_buf,
+        stderr: stderr_buf,
+    })
+}
+
+fn spawn_chunk_reader<R>(
+    mut reader: R,
+    tx: mpsc::Sender<Result<CaptureChunk>>,
+    wrap: fn(Vec<u8>) -> CaptureChunk,
+) where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if tx.send(Ok(wrap(buf[..n].to_vec()))).is_err() {
+                        break;
+                    }
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    let _ = tx.send(Err(Error::Io(err)));
+                    break;
+                }
+            }
         }
-        command
+    });
+}
+
+/// Program extensions [`Command::run_script`] checks for a shebang line.
+const SCRIPT_EXTENSIONS: &[&str] = &["sh", "bash", "py", "pl", "rb"];
+
+fn parse_shebang(line: &str) -> Option<(OsString, Vec<String>)> {
+    let mut tokens = line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = tokens.next()?;
+    if Path::new(interpreter).file_name() == Some(OsStr::new("env")) {
+        interpreter = tokens.by_ref().find(|token| !token.starts_with('-'))?;
     }
+    let args = tokens.map(str::to_string).collect();
+    let path = Path::new(interpreter);
+    let interpreter = if path.is_absolute() && !path.exists() {
+        path.file_name()?.to_os_string()
+    } else {
+        OsString::from(interpreter)
+    };
+    Some((interpreter, args))
 }
 
 /// Helper to create a [`Command`] from a program name.
@@ -506,7 +1192,21 @@ pub fn cmd(program: impl Into<OsString>) -> Command {
     Command::new(program)
 }
 
+/// Builds a [`Command`] from a full argv, using the first element as the program.
+///
+/// Returns an error when `argv` is empty.
+pub fn from_argv(argv: &[impl AsRef<OsStr>]) -> Result<Command> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| Error::Io(std::io::Error::other("empty argv")))?;
+    Ok(Command::new(program.as_ref()).args(args.iter().map(AsRef::as_ref)))
+}
+
 /// Executes a platform shell (`sh -c` or `cmd /C`).
+///
+/// The script is handed to the shell verbatim, so never interpolate untrusted
+/// input into it. Escape such values with [`quote`](crate::quote) or, on Unix,
+/// pass them as positional parameters via `sh_args`.
 pub fn sh(script: impl AsRef<str>) -> Command {
     let command = if cfg!(windows) {
         Command::new("cmd").arg("/C")
@@ -516,6 +1216,151 @@ pub fn sh(script: impl AsRef<str>) -> Command {
     command.arg(script.as_ref().to_string())
 }
 
+/// Like [`sh`], but runs the script inside `dir`.
+pub fn sh_in(dir: impl Into<PathBuf>, script: impl AsRef<str>) -> Command {
+    sh(script).current_dir(dir)
+}
+
+/// Runs `sh -c script` with `args` passed as positional parameters.
+///
+/// `$0` is set to `sh` and `args` become `$1`, `$2`, ..., so untrusted data can
+/// be referenced as `"$1"` instead of being interpolated into the script text.
+#[cfg(unix)]
+pub fn sh_args<I, S>(script: impl AsRef<str>, args: I) -> Command
+where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+{
+    sh(script).arg("sh").args(args)
+}
+
+fn shell_quote(word: &OsStr) -> String {
+    quote(&word.to_string_lossy())
+}
+
+/// Runs each command in order via [`Command::run`], stopping at the first failure.
+///
+/// The returned error is wrapped with the index and program of the failing command.
+pub fn run_all(commands: &[Command]) -> Result<()> {
+    for (idx, command) in commands.iter().enumerate() {
+        command
+            .run()
+            .map_err(|err| err.context(format!("command #{idx} ({:?}) failed", command.program)))?;
+    }
+    Ok(())
+}
+
+/// Runs `source`, passes each stdout line through `transform`, and feeds the
+/// results (newline-terminated) into `sink`, returning `sink`'s output.
+///
+/// Lines for which `transform` returns `None` are dropped. The sink's own stdin
+/// configuration is replaced, and `source` inherits stderr like a non-final
+/// [`Pipeline`] stage. A failing sink is reported before a failing source.
+pub fn bridge<F>(source: Command, mut transform: F, sink: Command) -> Result<CommandOutput>
+where
+    F: FnMut(String) -> Option<String> + Send + 'static,
+{
+    let mut command = source.build_std_command();
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::inherit());
+    let (mut child, stdin_handle) = spawn_with_stdin(&mut command, &source.stdin)?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| Error::Io(std::io::Error::other("missing stdout pipe")))?;
+    let lines = BufReader::new(stdout)
+        .lines()
+        .filter_map(move |line| match line {
+            Ok(line) => transform(line).map(Ok),
+            Err(err) => Some(Err(err)),
+        });
+    let sink_result = sink.stdin_reader(LinesReader::new(lines)).output();
+    if sink_result.is_err() {
+        let _ = child.kill();
+    }
+    let status = child.wait()?;
+    let stdin_result = wait_stdin_writer(stdin_handle);
+    let output = sink_result?;
+    if !status.success() {
+        return Err(Error::Command {
+            program: source.program,
+            status,
+            stderr: "stderr inherited by parent".into(),
+        });
+    }
+    stdin_result?;
+    Ok(output)
+}
+
+struct TimeoutLinesIter {
+    child: Option<Child>,
+    program: OsString,
+    rx: mpsc::Receiver<std::io::Result<String>>,
+    idle: Duration,
+    stdin_handle: Option<StdinJoinHandle>,
+    stderr_handle: Option<thread::JoinHandle<String>>,
+}
+
+impl TimeoutLinesIter {
+    fn kill(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    fn finish(&mut self) -> Option<Result<String>> {
+        let mut child = self.child.take()?;
+        let status = match child.wait() {
+            Ok(status) => status,
+            Err(err) => return Some(Err(err.into())),
+        };
+        let stderr = self
+            .stderr_handle
+            .take()
+            .map(|handle| handle.join().unwrap_or_default())
+            .unwrap_or_default();
+        let stdin_result = wait_stdin_writer(self.stdin_handle.take());
+        if !status.success() {
+            return Some(Err(Error::Command {
+                program: self.program.clone(),
+                status,
+                stderr,
+            }));
+        }
+        stdin_result.err().map(Err)
+    }
+}
+
+impl Iterator for TimeoutLinesIter {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.child.as_ref()?;
+        match self.rx.recv_timeout(self.idle) {
+            Ok(Ok(line)) => Some(Ok(line)),
+            Ok(Err(err)) => {
+                self.kill();
+                Some(Err(err.into()))
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                self.kill();
+                Some(Err(Error::Timeout {
+                    program: self.program.clone(),
+                    idle: self.idle,
+                }))
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => self.finish(),
+        }
+    }
+}
+
+impl Drop for TimeoutLinesIter {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
 /// Output of a successfully executed command.
 #[derive(Debug, Clone)]
 pub struct CommandOutput {
@@ -524,6 +1369,16 @@ pub struct CommandOutput {
     pub stderr: Vec<u8>,
 }
 
+/// Execution statistics returned by [`Command::run_metrics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandMetrics {
+    pub duration: Duration,
+    pub stdout_bytes: usize,
+    pub stderr_bytes: usize,
+    /// `None` when the process was terminated by a signal.
+    pub exit_code: Option<i32>,
+}
+
 impl CommandOutput {
     pub fn success(&self) -> bool {
         self.status.success()
@@ -536,4 +1391,19 @@ impl CommandOutput {
     pub fn stderr_string(&self) -> Result<String> {
         Ok(String::from_utf8(self.stderr.clone())?)
     }
+
+    /// Decodes stdout lossily without cloning when it is already valid UTF-8.
+    pub fn stdout_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stdout)
+    }
+
+    /// Decodes stderr lossily without cloning when it is already valid UTF-8.
+    pub fn stderr_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stderr)
+    }
+
+    /// Moves the captured stdout buffer out of the output.
+    pub fn into_stdout(self) -> Vec<u8> {
+        self.stdout
+    }
 }
@@ -14,7 +14,7 @@ use std::{
 use tokio::task;
 
 use super::{
-    Command, ReceiverIter, StdinJoinHandle, builder::CommandOutput, feed_child_stdin,
+    Command, ReceiverIter, StdinJoinHandle, builder::CommandOutput, spawn_with_stdin,
     wait_stdin_writer,
 };
 
@@ -22,6 +22,7 @@ use super::{
 #[derive(Debug, Clone)]
 pub struct Pipeline {
     stages: Vec<Command>,
+    checked: bool,
 }
 
 #[derive(Debug)]
@@ -44,15 +45,91 @@ impl Pipeline {
     pub fn new(first: Command, second: Command) -> Self {
         Self {
             stages: vec![first, second],
+            checked: false,
         }
     }
 
+    /// Builds a pipeline from any number of stages, failing early when empty.
+    ///
+    /// A single-stage pipeline behaves exactly like running that [`Command`].
+    pub fn from_stages(stages: impl IntoIterator<Item = Command>) -> Result<Self> {
+        let stages: Vec<Command> = stages.into_iter().collect();
+        if stages.is_empty() {
+            return Err(Error::Io(std::io::Error::other("empty pipeline")));
+        }
+        Ok(Self {
+            stages,
+            checked: false,
+        })
+    }
+
     /// Adds another stage to the pipeline.
     pub fn pipe(mut self, next: Command) -> Self {
         self.stages.push(next);
         self
     }
 
+    /// Runs [`Pipeline::validate`] before every execution, so a missing
+    /// program fails before any stage is spawned.
+    pub fn checked(mut self) -> Self {
+        self.checked = true;
+        self
+    }
+
+    /// Checks that every stage's program can be resolved on `PATH`.
+    ///
+    /// Relative program paths are resolved against the stage's working
+    /// directory. The error names the first missing stage and its index.
+    pub fn validate(&self) -> Result<()> {
+        for (idx, stage) in self.stages.iter().enumerate() {
+            if stage.resolve_program().is_none() {
+                return Err(Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!(
+                        "pipeline stage {idx}: program {:?} not found",
+                        stage.program
+                    ),
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the commands making up the pipeline, in execution order.
+    pub fn stages(&self) -> &[Command] {
+        &self.stages
+    }
+
+    /// Returns the number of stages.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns `true` when the pipeline has no stages.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Renders every stage via [`Command::to_shell_string`], joined with ` | `.
+    ///
+    /// Stages with a working directory are grouped in parentheses so the `cd`
+    /// only applies to that stage.
+    pub fn to_shell_string(&self) -> String {
+        let grouped = self.stages.len() > 1;
+        self.stages
+            .iter()
+            .map(|stage| {
+                let rendered = stage.to_shell_string();
+                if grouped && stage.current_dir.is_some() {
+                    format!("({rendered})")
+                } else {
+                    rendered
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
     /// Executes the pipeline and returns the last stage's output.
     pub fn output(&self) -> Result<CommandOutput> {
         let (running, final_stage) = self.spawn_pipeline(true, true, false, false)?;
@@ -83,6 +160,31 @@ impl Pipeline {
         self.output()?.stdout_string()
     }
 
+    /// Returns the last stage's stdout while its stderr, like every earlier
+    /// stage's, is inherited from the parent.
+    pub fn stdout_text_inherit_stderr(&self) -> Result<String> {
+        let (running, final_stage) = self.spawn_pipeline(true, false, false, false)?;
+        let FinalStage {
+            child,
+            program,
+            stdin_handle,
+            ..
+        } = final_stage;
+        let output = child.wait_with_output()?;
+        wait_stdin_writer(stdin_handle)?;
+        let running_result = wait_running_stages(running);
+        if !output.status.success() {
+            let _ = running_result;
+            return Err(Error::Command {
+                program,
+                status: output.status,
+                stderr: "stderr inherited by parent".into(),
+            });
+        }
+        running_result?;
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
     /// Executes the pipeline ignoring stdout/stderr, returning only success.
     pub fn run(&self) -> Result<()> {
         let (running, final_stage) = self.spawn_pipeline(false, false, false, false)?;
@@ -403,6 +505,9 @@ impl Pipeline {
         if self.stages.is_empty() {
             return Err(Error::Io(std::io::Error::other("empty pipeline")));
         }
+        if self.checked {
+            self.validate()?;
+        }
         debug_assert!(!take_final_stdout || capture_final_stdout);
         debug_assert!(!take_final_stderr || capture_final_stderr);
         let mut previous_stdout: Option<ChildStdout> = None;
@@ -434,12 +539,12 @@ impl Pipeline {
                 command.stderr(Stdio::inherit());
             }
 
-            let mut child = command.spawn()?;
-            let stdin_handle = if uses_pipeline_input {
-                None
+            let stdin = if uses_pipeline_input {
+                &None
             } else {
-                feed_child_stdin(&mut child, &stage.stdin)?
+                &stage.stdin
             };
+            let (mut child, stdin_handle) = spawn_with_stdin(&mut command, stdin)?;
 
             if is_last {
                 let stdout_handle =
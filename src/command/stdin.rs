@@ -1,18 +1,24 @@
 use crate::{Error, Result};
 
+use super::Command;
+
 use std::{
     fmt,
-    io::{Read, Write},
-    process::Child,
+    io::{Cursor, Read, Write},
+    process::{Child, Command as StdCommand},
     sync::{Arc, Mutex},
     thread,
 };
 
 pub type StdinJoinHandle = thread::JoinHandle<std::io::Result<()>>;
 
+/// Writers that receive a copy of a producer's stdout via [`Command::stdin_tee`].
+pub type ProducerSinks = Mutex<Vec<Box<dyn Write + Send>>>;
+
 pub enum StdinSource {
     Bytes(Vec<u8>),
     Reader(Arc<Mutex<Option<Box<dyn Read + Send>>>>),
+    Command(Box<Command>, ProducerSinks),
 }
 
 impl StdinSource {
@@ -23,24 +29,164 @@ impl StdinSource {
         StdinSource::Reader(Arc::new(Mutex::new(Some(Box::new(reader)))))
     }
 
+    /// Wraps the source so every byte fed to the child is also written to `sink`.
+    pub fn tee<W>(self, sink: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        match self {
+            StdinSource::Bytes(data) => {
+                StdinSource::reader(TeeReader::new(Cursor::new(data), sink))
+            }
+            StdinSource::Reader(shared) => {
+                let inner = shared.lock().unwrap().take();
+                match inner {
+                    Some(reader) => StdinSource::reader(TeeReader::new(reader, sink)),
+                    None => StdinSource::Reader(shared),
+                }
+            }
+            StdinSource::Command(producer, sinks) => {
+                sinks.lock().unwrap().push(Box::new(sink));
+                StdinSource::Command(producer, sinks)
+            }
+        }
+    }
+
+    /// Runs a producer source to completion, returning the bytes it produced.
+    ///
+    /// Other sources return `None`; they are fed once the child is spawned.
+    pub fn produce(&self) -> Result<Option<Vec<u8>>> {
+        let StdinSource::Command(producer, sinks) = self else {
+            return Ok(None);
+        };
+        let stdout = producer.output()?.stdout;
+        copy_to_sinks(sinks, &stdout)?;
+        Ok(Some(stdout))
+    }
+
     pub fn try_clone(&self) -> Option<Self> {
         match self {
             StdinSource::Bytes(data) => Some(StdinSource::Bytes(data.clone())),
             StdinSource::Reader(_) => None,
+            StdinSource::Command(producer, sinks) => sinks
+                .lock()
+                .unwrap()
+                .is_empty()
+                .then(|| StdinSource::Command(producer.clone(), Mutex::default())),
         }
     }
 }
 
+/// Adapts an iterator of lines into a [`Read`] that appends `\n` after each item.
+pub struct LinesReader<I> {
+    lines: I,
+    pending: Vec<u8>,
+    pos: usize,
+}
+
+impl<I> LinesReader<I> {
+    pub fn new(lines: I) -> Self {
+        Self {
+            lines,
+            pending: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl<I> Read for LinesReader<I>
+where
+    I: Iterator<Item = std::io::Result<String>>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos >= self.pending.len() {
+            match self.lines.next().transpose()? {
+                Some(line) => {
+                    self.pending = line.into_bytes();
+                    self.pending.push(b'\n');
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let available = &self.pending[self.pos..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Reader that copies everything it yields into a secondary sink.
+pub struct TeeReader<R, W> {
+    reader: R,
+    sink: W,
+}
+
+impl<R, W> TeeReader<R, W> {
+    pub fn new(reader: R, sink: W) -> Self {
+        Self { reader, sink }
+    }
+}
+
+impl<R, W> Read for TeeReader<R, W>
+where
+    R: Read,
+    W: Write,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        if n == 0 {
+            self.sink.flush()?;
+        } else {
+            self.sink.write_all(&buf[..n])?;
+        }
+        Ok(n)
+    }
+}
+
 impl fmt::Debug for StdinSource {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             StdinSource::Bytes(data) => f.debug_tuple("Bytes").field(&data.len()).finish(),
             StdinSource::Reader(_) => f.write_str("Reader(..)"),
+            StdinSource::Command(producer, _) => f.debug_tuple("Command").field(producer).finish(),
         }
     }
 }
 
-pub fn feed_child_stdin(
+/// Writes a producer's output to every sink registered by [`Command::stdin_tee`].
+pub fn copy_to_sinks(sinks: &ProducerSinks, data: &[u8]) -> Result<()> {
+    for sink in sinks.lock().unwrap().iter_mut() {
+        sink.write_all(data)?;
+        sink.flush()?;
+    }
+    Ok(())
+}
+
+/// Spawns `command` and starts feeding it `source`.
+///
+/// A producer source runs to completion first, so when it fails the child is
+/// never spawned.
+pub fn spawn_with_stdin(
+    command: &mut StdCommand,
+    source: &Option<StdinSource>,
+) -> Result<(Child, Option<StdinJoinHandle>)> {
+    let produced = match source {
+        Some(source) => source.produce()?.map(StdinSource::Bytes),
+        None => None,
+    };
+    let source = if produced.is_some() {
+        &produced
+    } else {
+        source
+    };
+    let mut child = command.spawn()?;
+    let stdin_handle = feed_child_stdin(&mut child, source)?;
+    Ok((child, stdin_handle))
+}
+
+fn feed_child_stdin(
     child: &mut Child,
     source: &Option<StdinSource>,
 ) -> Result<Option<StdinJoinHandle>> {
@@ -73,6 +219,9 @@ pub fn feed_child_stdin(
             });
             Ok(Some(handle))
         }
+        Some(StdinSource::Command(..)) => {
+            unreachable!("producer stdin is resolved before the child is spawned")
+        }
         None => Ok(None),
     }
 }
@@ -174,3 +174,627 @@ fn cloning_command_drops_stdin_reader() -> Result<()> {
     clone.inherit_stdin(true).run()?;
     Ok(())
 }
+
+#[test]
+fn pipeline_exposes_stages() {
+    let pipeline = cmd("echo").arg("hi").pipe(cmd("tr").args(["a-z", "A-Z"]));
+    assert_eq!(pipeline.len(), 2);
+    assert!(!pipeline.is_empty());
+    let programs: Vec<_> = pipeline
+        .stages()
+        .iter()
+        .map(|stage| stage.get_program().to_owned())
+        .collect();
+    assert_eq!(programs, vec!["echo", "tr"]);
+    assert_eq!(pipeline.stages()[1].get_args(), ["a-z", "A-Z"]);
+}
+
+#[test]
+fn for_each_line_pushes_lines_in_order() -> Result<()> {
+    let mut seen = Vec::new();
+    let status = sh("echo one && echo two && echo three").for_each_line(|line| {
+        seen.push(line.trim().to_string());
+    })?;
+    assert!(status.success());
+    assert_eq!(seen, vec!["one", "two", "three"]);
+
+    let mut failed = Vec::new();
+    let status =
+        sh("echo partial && exit 3").for_each_line(|line| failed.push(line.to_string()))?;
+    assert_eq!(status.code(), Some(3));
+    assert_eq!(failed.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn pipeline_from_stages() -> Result<()> {
+    assert!(Pipeline::from_stages(Vec::new()).is_err());
+
+    let single = Pipeline::from_stages([sh("echo solo")])?;
+    assert_eq!(single.len(), 1);
+    assert_eq!(single.stdout_text()?, sh("echo solo").stdout_text()?);
+    assert!(Pipeline::from_stages([sh("exit 2")])?.run().is_err());
+
+    let stages = vec![sh("echo staged"), stdin_passthrough_command()];
+    let output = Pipeline::from_stages(stages)?.stdout_text()?;
+    assert!(output.contains("staged"));
+    Ok(())
+}
+
+#[test]
+fn pipeline_validate_reports_missing_stage() -> Result<()> {
+    let pipeline = Pipeline::from_stages([
+        sh("echo hi"),
+        cmd("qshr-definitely-missing-binary"),
+        stdin_passthrough_command(),
+    ])?;
+    let err = pipeline.validate().unwrap_err().to_string();
+    assert!(err.contains("stage 1"), "{err}");
+    assert!(err.contains("qshr-definitely-missing-binary"), "{err}");
+    assert!(pipeline.clone().checked().run().is_err());
+
+    let valid = sh("echo ok").pipe(stdin_passthrough_command()).checked();
+    valid.validate()?;
+    assert!(valid.stdout_text()?.contains("ok"));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn to_shell_string_quotes_and_roundtrips() -> Result<()> {
+    let command = cmd("printf")
+        .arg("%s|%s|%s\\n")
+        .arg("two words")
+        .arg("it's $HOME")
+        .arg("")
+        .env("GREETING", "hi there")
+        .current_dir("/tmp");
+    assert_eq!(
+        command.to_shell_string(),
+        "cd /tmp && GREETING='hi there' printf '%s|%s|%s\\n' 'two words' 'it'\\''s $HOME' ''"
+    );
+    assert_eq!(
+        sh(command.to_shell_string()).stdout_text()?,
+        command.stdout_text()?
+    );
+
+    let pipeline = cmd("echo")
+        .arg("a b")
+        .current_dir("/tmp")
+        .pipe(cmd("tr").args(["a-z", "A-Z"]));
+    assert_eq!(
+        pipeline.to_shell_string(),
+        "(cd /tmp && echo 'a b') | tr a-z A-Z"
+    );
+    assert_eq!(sh(pipeline.to_shell_string()).stdout_text()?, "A B\n");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn max_output_bytes_kills_chatty_children() -> Result<()> {
+    let err = cmd("yes").max_output_bytes(64 * 1024).output().unwrap_err();
+    assert!(matches!(err, crate::Error::OutputTooLarge { limit } if limit == 64 * 1024));
+
+    let output = sh("echo small").max_output_bytes(1024).output()?;
+    assert_eq!(output.stdout_string()?.trim(), "small");
+    Ok(())
+}
+
+#[test]
+fn run_all_stops_at_first_failure() -> Result<()> {
+    let dir = tempdir()?;
+    let first = dir.path().join("first.txt");
+    let third = dir.path().join("third.txt");
+    let commands = [
+        sh(format!("echo one > \"{}\"", first.display())),
+        sh("exit 1"),
+        sh(format!("echo three > \"{}\"", third.display())),
+    ];
+    let err = run_all(&commands).unwrap_err();
+    assert!(matches!(&err, crate::Error::Context { context, .. } if context.contains("#1")));
+    assert!(first.exists());
+    assert!(!third.exists());
+
+    run_all(&commands[..1])?;
+    Ok(())
+}
+
+#[test]
+fn stdin_lines_streams_generated_lines() -> Result<()> {
+    let output = stdin_passthrough_command()
+        .stdin_lines((0..3).map(|n| format!("line-{n}")))
+        .stdout_text()?;
+    let lines: Vec<_> = output.lines().map(str::trim).collect();
+    assert_eq!(lines, vec!["line-0", "line-1", "line-2"]);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn env_inherit_filter_drops_rejected_vars() -> Result<()> {
+    crate::set_var("QSHR_FILTER_SECRET_TOKEN", "hidden");
+    crate::set_var("QSHR_FILTER_VISIBLE", "shown");
+    let output = sh("env")
+        .env_inherit_filter(|key, _| !key.to_string_lossy().contains("SECRET"))
+        .env("QSHR_FILTER_VISIBLE", "override")
+        .stdout_text()?;
+    crate::remove_var("QSHR_FILTER_SECRET_TOKEN");
+    crate::remove_var("QSHR_FILTER_VISIBLE");
+    assert!(!output.contains("QSHR_FILTER_SECRET_TOKEN"));
+    assert!(output.contains("QSHR_FILTER_VISIBLE=override"));
+    assert!(output.contains("PATH="));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn retry_reruns_failing_commands() -> Result<()> {
+    use std::time::Duration;
+
+    let dir = tempdir()?;
+    let counter = dir.path().join("attempts");
+    let flaky = |counter: &std::path::Path| {
+        sh(format!(
+            "n=$(cat \"{0}\" 2>/dev/null || echo 0); n=$((n+1)); echo $n > \"{0}\"; [ $n -ge 3 ]",
+            counter.display()
+        ))
+    };
+    let attempts = |counter: &std::path::Path| -> Result<String> {
+        Ok(std::fs::read_to_string(counter)?.trim().to_string())
+    };
+
+    flaky(&counter).retry(5, Duration::from_millis(1)).run()?;
+    assert_eq!(attempts(&counter)?, "3");
+
+    std::fs::remove_file(&counter)?;
+    let err = flaky(&counter)
+        .retry(1, Duration::from_millis(1))
+        .retry_multiplier(3)
+        .output()
+        .unwrap_err();
+    assert!(matches!(err, crate::Error::Command { .. }));
+    assert_eq!(attempts(&counter)?, "2");
+
+    std::fs::remove_file(&counter)?;
+    let status = flaky(&counter).retry(5, Duration::ZERO).status()?;
+    assert!(status.success());
+    let status = flaky(&counter).retry(5, Duration::ZERO).status()?;
+    assert!(status.success());
+    assert_eq!(attempts(&counter)?, "4");
+    Ok(())
+}
+
+#[test]
+fn command_output_lossy_accessors() -> Result<()> {
+    let output = sh("echo out && echo err 1>&2").output()?;
+    assert!(matches!(
+        output.stdout_lossy(),
+        std::borrow::Cow::Borrowed(_)
+    ));
+    assert_eq!(output.stdout_lossy().trim(), "out");
+    assert_eq!(output.stderr_lossy().trim(), "err");
+
+    let invalid = CommandOutput {
+        status: output.status,
+        stdout: vec![b'a', 0xff],
+        stderr: Vec::new(),
+    };
+    assert_eq!(invalid.stdout_lossy(), "a\u{fffd}");
+    assert_eq!(invalid.into_stdout(), vec![b'a', 0xff]);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn env_last_write_wins() -> Result<()> {
+    use std::ffi::OsStr;
+
+    let command = sh("echo $QSHR_DEDUP")
+        .env("QSHR_DEDUP", "1")
+        .envs([("QSHR_OTHER", "x"), ("QSHR_DEDUP", "2")]);
+    let envs: Vec<_> = command.get_envs().collect();
+    assert_eq!(
+        envs,
+        vec![
+            (OsStr::new("QSHR_DEDUP"), Some(OsStr::new("2"))),
+            (OsStr::new("QSHR_OTHER"), Some(OsStr::new("x"))),
+        ]
+    );
+    assert_eq!(command.stdout_text()?.trim(), "2");
+
+    crate::set_var("QSHR_DEDUP_INHERITED", "parent");
+    let removed = sh("echo \"[$QSHR_DEDUP_INHERITED]\"")
+        .env("QSHR_DEDUP_INHERITED", "child")
+        .env_remove("QSHR_DEDUP_INHERITED");
+    let output = removed.stdout_text()?;
+    crate::remove_var("QSHR_DEDUP_INHERITED");
+    assert_eq!(output.trim(), "[]");
+    assert_eq!(removed.get_envs().count(), 1);
+    Ok(())
+}
+
+#[test]
+fn sh_in_runs_in_directory() -> Result<()> {
+    let dir = tempdir()?;
+    std::fs::write(dir.path().join("marker.txt"), "here")?;
+    let listing = sh_in(dir.path(), if cfg!(windows) { "dir /b" } else { "ls" }).stdout_text()?;
+    assert!(listing.contains("marker.txt"));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn sh_args_passes_positional_parameters() -> Result<()> {
+    let hostile = "$(echo pwned); 'quoted' \"double\"";
+    let output = sh_args("printf '%s|%s' \"$1\" \"$2\"", [hostile, "second"]).stdout_text()?;
+    assert_eq!(output, format!("{hostile}|second"));
+    Ok(())
+}
+
+#[test]
+fn stdin_tee_records_fed_bytes() -> Result<()> {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let reader_tee = SharedBuf::default();
+    let output = stdin_passthrough_command()
+        .stdin_reader(Cursor::new(b"from reader\n".to_vec()))
+        .stdin_tee(reader_tee.clone())
+        .output()?;
+    assert_eq!(*reader_tee.0.lock().unwrap(), output.stdout);
+
+    let bytes_tee = SharedBuf::default();
+    let output = stdin_passthrough_command()
+        .stdin("from bytes\n")
+        .stdin_tee(bytes_tee.clone())
+        .output()?;
+    assert_eq!(*bytes_tee.0.lock().unwrap(), output.stdout);
+    assert_eq!(&*bytes_tee.0.lock().unwrap(), b"from bytes\n");
+    Ok(())
+}
+
+#[test]
+fn bridge_transforms_lines_between_commands() -> Result<()> {
+    let source = stdin_passthrough_command().stdin("Hello\nSKIP me\nWORLD\n");
+    let output = bridge(
+        source,
+        |line| (!line.starts_with("SKIP")).then(|| line.trim().to_lowercase()),
+        stdin_passthrough_command(),
+    )?;
+    let lines: Vec<_> = output
+        .stdout_lossy()
+        .lines()
+        .map(str::trim)
+        .map(String::from)
+        .collect();
+    assert_eq!(lines, vec!["hello", "world"]);
+
+    assert!(bridge(sh("exit 3"), Some, stdin_passthrough_command()).is_err());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn arg_bytes_passes_non_utf8_arguments() -> Result<()> {
+    let raw = b"caf\xe9-\xff.txt";
+    let output = cmd("printf").arg("%s").arg_bytes(raw).output()?;
+    assert_eq!(output.stdout, raw);
+    Ok(())
+}
+
+#[test]
+fn from_argv_splits_program_and_args() -> Result<()> {
+    let argv = if cfg!(windows) {
+        vec!["cmd", "/C", "echo argv"]
+    } else {
+        vec!["sh", "-c", "echo argv"]
+    };
+    let command = from_argv(&argv)?;
+    assert_eq!(command.get_program(), argv[0]);
+    assert_eq!(command.get_args().len(), 2);
+    assert_eq!(command.stdout_text()?.trim(), "argv");
+
+    let empty: [&str; 0] = [];
+    assert!(from_argv(&empty).is_err());
+    Ok(())
+}
+
+#[cfg(unix)]
+fn env_order_command() -> Command {
+    crate::set_var("QSHR_ORDER_KEPT", "parent");
+    crate::set_var("QSHR_ORDER_SET", "parent");
+    crate::set_var("QSHR_ORDER_DROPPED", "parent");
+    let command =
+        sh("echo \"$QSHR_ORDER_KEPT|$QSHR_ORDER_SET|$QSHR_ORDER_DROPPED|$QSHR_ORDER_OTHER\"")
+            .env_remove("QSHR_ORDER_DROPPED")
+            .env("QSHR_ORDER_SET", "explicit")
+            .env_inherit_filter(|key, _| key.to_string_lossy().starts_with("QSHR_ORDER_"))
+            .clear_env();
+    crate::set_var("QSHR_ORDER_OTHER", "late");
+    command
+}
+
+#[cfg(unix)]
+#[test]
+fn env_overrides_apply_in_documented_order() -> Result<()> {
+    let command = env_order_command();
+    let output = command.stdout_text()?;
+    let piped = command.clone().pipe(cmd("cat")).stdout_text()?;
+    crate::remove_var("QSHR_ORDER_OTHER");
+    assert_eq!(output.trim(), "parent|explicit||");
+    assert_eq!(piped.trim(), "parent|explicit||");
+    let envs: Vec<_> = command.get_envs().collect();
+    assert_eq!(envs.len(), 2);
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "async"))]
+#[tokio::test]
+async fn async_env_overrides_match_sync_order() -> Result<()> {
+    let output = env_order_command().output_async().await?;
+    crate::remove_var("QSHR_ORDER_OTHER");
+    assert_eq!(output.stdout_lossy().trim(), "parent|explicit||");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn signal_exits_name_the_signal() {
+    let err = sh("kill -TERM $$").output().unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("terminated by signal 15 (SIGTERM)"),
+        "{message}"
+    );
+}
+
+#[test]
+fn stdin_from_feeds_producer_output() -> Result<()> {
+    let producer = sh("echo chained");
+    let output = stdin_passthrough_command().stdin_from(producer).output()?;
+    assert_eq!(output.stdout_lossy().trim(), "chained");
+
+    let err = stdin_passthrough_command()
+        .stdin_from(sh("exit 4"))
+        .output()
+        .unwrap_err();
+    assert!(matches!(err, crate::Error::Command { .. }), "{err}");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn stdin_from_failing_producer_never_spawns_consumer() -> Result<()> {
+    let dir = tempdir()?;
+    let marker = dir.path().join("consumer-ran");
+    let consumer = sh("touch consumer-ran; cat").current_dir(dir.path());
+
+    let err = consumer
+        .clone()
+        .stdin_from(sh("echo partial; exit 3"))
+        .output()
+        .unwrap_err();
+    assert!(matches!(err, crate::Error::Command { .. }), "{err}");
+    assert!(consumer.clone().stdin_from(sh("exit 3")).run().is_err());
+    assert!(!marker.exists());
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    struct ChannelSink(std::sync::mpsc::Sender<Vec<u8>>);
+    impl std::io::Write for ChannelSink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let _ = self.0.send(buf.to_vec());
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let output = consumer
+        .stdin_from(sh("echo fed"))
+        .stdin_tee(ChannelSink(tx))
+        .output()?;
+    assert_eq!(output.stdout_lossy(), "fed\n");
+    assert_eq!(rx.try_iter().flatten().collect::<Vec<_>>(), b"fed\n");
+    assert!(marker.exists());
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn stdin_from_works_in_async_mode() -> Result<()> {
+    let output = stdin_passthrough_command()
+        .stdin_from(sh("echo async-chained"))
+        .output_async()
+        .await?;
+    assert_eq!(output.stdout_lossy().trim(), "async-chained");
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "async"))]
+#[tokio::test]
+async fn async_output_drains_both_pipes_while_writing_stdin() -> Result<()> {
+    // `tee` echoes stdin to both streams; with 4 MiB in flight every pipe fills
+    // up unless stdin, stdout and stderr are all serviced at once.
+    let input = vec![b'x'; 4 * 1024 * 1024];
+    let output = cmd("tee")
+        .arg("/dev/stderr")
+        .stdin(input.clone())
+        .output_async()
+        .await?;
+    assert_eq!(output.stdout.len(), input.len());
+    assert_eq!(output.stderr.len(), input.len());
+    Ok(())
+}
+
+#[test]
+fn is_available_checks_program_without_spawning() -> Result<()> {
+    let shell = if cfg!(windows) { "cmd" } else { "sh" };
+    assert!(cmd(shell).is_available());
+    assert!(!cmd("qshr-definitely-missing-binary").is_available());
+
+    let dir = tempdir()?;
+    std::fs::write(dir.path().join("tool.sh"), "echo hi")?;
+    let relative = format!(".{}tool.sh", std::path::MAIN_SEPARATOR);
+    assert!(cmd(&relative).current_dir(dir.path()).is_available());
+    assert!(!cmd(&relative).is_available());
+    Ok(())
+}
+
+#[test]
+fn run_metrics_reports_captured_bytes() -> Result<()> {
+    let (output, metrics) = sh("echo metrics; echo oops 1>&2").run_metrics()?;
+    assert_eq!(metrics.stdout_bytes, output.stdout.len());
+    assert_eq!(metrics.stderr_bytes, output.stderr.len());
+    assert!(metrics.stdout_bytes > 0);
+    assert!(metrics.stderr_bytes > 0);
+    assert_eq!(metrics.exit_code, Some(0));
+    assert!(metrics.duration > std::time::Duration::ZERO);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn stream_lines_timeout_kills_idle_child() -> Result<()> {
+    use std::time::Duration;
+
+    let started = std::time::Instant::now();
+    let mut lines =
+        sh("echo first; exec sleep 30").stream_lines_timeout(Duration::from_millis(300))?;
+    assert_eq!(lines.next().transpose()?, Some("first".to_string()));
+    let err = lines.next().unwrap().unwrap_err();
+    assert!(matches!(err, crate::Error::Timeout { .. }), "{err}");
+    assert!(lines.next().is_none());
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    let finished: Vec<_> = sh("echo a; echo b")
+        .stream_lines_timeout(Duration::from_secs(5))?
+        .collect_results()?;
+    assert_eq!(finished, vec!["a", "b"]);
+    assert!(
+        sh("exit 3")
+            .stream_lines_timeout(Duration::from_secs(5))?
+            .collect_results()
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn output_with_progress_reports_stdout_size() -> Result<()> {
+    let mut reports = Vec::new();
+    let output = sh("i=0; while [ $i -lt 2000 ]; do echo line-$i; i=$((i+1)); done")
+        .output_with_progress(|bytes| reports.push(bytes))?;
+    assert!(!reports.is_empty());
+    assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(reports.last(), Some(&output.stdout.len()));
+    assert!(output.stdout_string()?.contains("line-1999"));
+    Ok(())
+}
+
+#[test]
+fn run_teeing_returns_printed_lines() -> Result<()> {
+    let lines = sh("echo one; echo two").run_teeing()?;
+    assert_eq!(lines, vec!["one", "two"]);
+    assert!(sh("echo partial; exit 4").run_teeing().is_err());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn stdout_text_inherit_stderr_captures_only_stdout() -> Result<()> {
+    let text = sh("echo out; echo err 1>&2").stdout_text_inherit_stderr()?;
+    assert_eq!(text, "out\n");
+
+    let text = sh("echo out; echo err 1>&2")
+        .pipe(sh("cat; echo tail-err 1>&2"))
+        .stdout_text_inherit_stderr()?;
+    assert_eq!(text, "out\n");
+
+    assert!(sh("exit 3").stdout_text_inherit_stderr().is_err());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn output_reads_null_stdin_by_default() -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    // The suite itself runs with stdin at EOF, so re-run this test in a child
+    // whose stdin is a pipe that stays open and never receives data.
+    if std::env::var_os("QSHR_OPEN_STDIN_CHILD").is_some() {
+        let output = cmd("cat").output()?;
+        assert!(output.stdout.is_empty());
+        assert!(cmd("cat").status()?.success());
+        return Ok(());
+    }
+
+    let mut child = std::process::Command::new(std::env::current_exe()?)
+        .args([
+            "command::tests::output_reads_null_stdin_by_default",
+            "--exact",
+        ])
+        .env("QSHR_OPEN_STDIN_CHILD", "1")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    let _open_stdin = child.stdin.take();
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > Duration::from_secs(10) {
+            let _ = child.kill();
+            let _ = child.wait();
+            panic!("child blocked reading the inherited stdin");
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    assert!(status.success());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn run_script_uses_shebang_interpreter() -> Result<()> {
+    let dir = tempdir()?;
+    let script = dir.path().join("greet.sh");
+    std::fs::write(&script, "#!/bin/sh\necho \"hello $1\"\n")?;
+    let output = cmd("greet.sh")
+        .arg("there")
+        .current_dir(dir.path())
+        .run_script()?;
+    assert_eq!(output.stdout_string()?, "hello there\n");
+    Ok(())
+}
+
+#[test]
+fn run_script_runs_python_via_env_shebang() -> Result<()> {
+    if crate::which("python3").is_none() {
+        return Ok(());
+    }
+    let dir = tempdir()?;
+    let script = dir.path().join("greet.py");
+    std::fs::write(
+        &script,
+        "#!/usr/bin/env python3\nimport sys\nprint('py', sys.argv[1])\n",
+    )?;
+    let output = cmd(&script).arg("ok").run_script()?;
+    assert_eq!(output.stdout_string()?.trim_end(), "py ok");
+    Ok(())
+}
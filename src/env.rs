@@ -94,6 +94,23 @@ pub fn which(program: impl AsRef<OsStr>) -> Option<PathBuf> {
     None
 }
 
+/// Like [`which`], but also tries `program.<ext>` for every extension in `exts`.
+///
+/// Unlike the Windows `PATHEXT` handling this works on every platform, so
+/// `which_ext("tool", &["sh", "py"])` can locate `tool.sh`. The exact name is
+/// tried first, then each extension in order.
+pub fn which_ext(program: impl AsRef<OsStr>, exts: &[&str]) -> Option<PathBuf> {
+    let program = program.as_ref();
+    which(program).or_else(|| {
+        exts.iter().find_map(|ext| {
+            let mut name = program.to_os_string();
+            name.push(".");
+            name.push(ext.trim_start_matches('.'));
+            which(name)
+        })
+    })
+}
+
 #[cfg(windows)]
 fn pathext_extensions() -> Vec<String> {
     env::var_os("PATHEXT")
@@ -151,6 +168,25 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn which_ext_tries_script_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("qshr-which-ext-tool.sh");
+        std::fs::write(&script, b"echo hi").unwrap();
+
+        let original = var("PATH").unwrap_or_default();
+        let mut paths = env::split_paths(&original).collect::<Vec<_>>();
+        paths.push(dir.path().to_path_buf());
+        set_var("PATH", env::join_paths(paths).unwrap());
+        let plain = which("qshr-which-ext-tool");
+        let found = which_ext("qshr-which-ext-tool", &["py", ".sh"]);
+        set_var("PATH", original);
+
+        assert!(plain.is_none());
+        assert_eq!(found, Some(script));
+    }
+
     #[test]
     fn which_ignores_directories() {
         let dir = tempfile::tempdir().unwrap();
@@ -1,5 +1,6 @@
 use std::{
-    error::Error as StdError, ffi::OsString, fmt, io, process::ExitStatus, string::FromUtf8Error,
+    error::Error as StdError, ffi::OsString, fmt, io, path::PathBuf, process::ExitStatus,
+    string::FromUtf8Error, time::Duration,
 };
 
 use glob::{GlobError, PatternError};
@@ -21,6 +22,31 @@ pub enum Error {
     GlobPattern(PatternError),
     Glob(GlobError),
     Notify(NotifyError),
+    Walk {
+        path: PathBuf,
+        source: io::Error,
+    },
+    OutputTooLarge {
+        limit: usize,
+    },
+    Timeout {
+        program: OsString,
+        idle: Duration,
+    },
+    Context {
+        context: String,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Wraps the error with a message describing what was being attempted.
+    pub fn context(self, context: impl Into<String>) -> Self {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -32,6 +58,14 @@ impl fmt::Display for Error {
                 status,
                 stderr,
             } => {
+                #[cfg(unix)]
+                if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(status) {
+                    write!(f, "command {:?} terminated by signal {signal}", program)?;
+                    if let Some(name) = signal_name(signal) {
+                        write!(f, " ({name})")?;
+                    }
+                    return write!(f, " (stderr: {stderr})");
+                }
                 write!(
                     f,
                     "command {:?} exited with {status} (stderr: {stderr})",
@@ -42,10 +76,41 @@ impl fmt::Display for Error {
             Error::GlobPattern(err) => write!(f, "invalid glob pattern: {err}"),
             Error::Glob(err) => write!(f, "glob resolution failed: {err}"),
             Error::Notify(err) => write!(f, "file watcher error: {err}"),
+            Error::Walk { path, source } => {
+                write!(f, "failed to walk {}: {source}", path.display())
+            }
+            Error::OutputTooLarge { limit } => {
+                write!(f, "captured output exceeded the {limit} byte limit")
+            }
+            Error::Timeout { program, idle } => {
+                write!(f, "command {:?} produced no output for {idle:?}", program)
+            }
+            Error::Context { context, source } => write!(f, "{context}: {source}"),
         }
     }
 }
 
+/// Names for the signals whose numbers agree across common Unix platforms.
+#[cfg(unix)]
+fn signal_name(signal: i32) -> Option<&'static str> {
+    let name = match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        _ => return None,
+    };
+    Some(name)
+}
+
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
@@ -54,7 +119,9 @@ impl StdError for Error {
             Error::GlobPattern(err) => Some(err),
             Error::Glob(err) => Some(err),
             Error::Notify(err) => Some(err),
-            Error::Command { .. } => None,
+            Error::Walk { source, .. } => Some(source),
+            Error::Context { source, .. } => Some(source.as_ref()),
+            Error::Command { .. } | Error::OutputTooLarge { .. } | Error::Timeout { .. } => None,
         }
     }
 }
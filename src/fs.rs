@@ -6,15 +6,37 @@ mod walk;
 mod watch;
 
 pub use entries::PathEntry;
-pub use filter::{filter_extension, filter_modified_since, filter_size};
+#[cfg(feature = "regex")]
+pub use filter::filter_matches;
+pub use filter::{
+    filter_contains, filter_extension, filter_glob, filter_modified_before,
+    filter_modified_between, filter_modified_since, filter_size, filter_size_max,
+    filter_size_range,
+};
 pub use glob::watch_glob;
-pub use glob::{GlobCache, glob, glob_entries};
+pub use glob::{
+    GlobCache, GlobOptions, glob, glob_count, glob_entries, glob_entries_with, glob_with,
+};
+#[cfg(feature = "trash")]
+pub use io::rm_to_trash;
 pub use io::{
-    append_text, cat, copy_dir, copy_entries, copy_file, mkdir_all, move_path, read_lines,
-    read_text, rm, temp_file, write_lines, write_text,
+    CopyDirOptions, CopyOptions, CopyProgress, ReadOptions, append_lines_from, append_text, cat,
+    cat_glob, copy_dir, copy_dir_incremental, copy_dir_with, copy_entries, copy_file,
+    copy_file_if_newer, copy_file_with, join_files, mkdir_all, move_path, read_lines,
+    read_lines_range, read_lines_rev, read_lines_with, read_text, rm, split_file, tail, tail_bytes,
+    temp_dir, temp_file, with_temp_file, write_lines, write_lines_from, write_text,
+    write_text_atomic,
+};
+#[cfg(feature = "parallel")]
+pub use walk::walk_filter_parallel;
+pub use walk::{
+    WalkBuilder, ls, ls_detailed, walk, walk_bfs, walk_depth, walk_detailed, walk_detailed_depth,
+    walk_files, walk_filter,
+};
+pub use watch::{
+    WatchEvent, Watcher, debounce_watch, watch, watch_channel, watch_coalesced, watch_filtered,
+    watch_paths,
 };
-pub use walk::{ls, ls_detailed, walk, walk_detailed, walk_files, walk_filter};
-pub use watch::{WatchEvent, Watcher, debounce_watch, watch, watch_channel, watch_filtered};
 #[cfg(feature = "async")]
 pub use watch::{watch_async, watch_async_stream, watch_filtered_async};
 
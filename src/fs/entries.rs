@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     ffi::OsStr,
     fs,
     path::{Path, PathBuf},
@@ -20,6 +21,14 @@ impl PathEntry {
         self.metadata.is_file()
     }
 
+    pub fn is_symlink(&self) -> bool {
+        self.metadata.file_type().is_symlink()
+    }
+
+    pub fn symlink_target(&self) -> Option<PathBuf> {
+        fs::read_link(&self.path).ok()
+    }
+
     pub fn file_name(&self) -> Option<&OsStr> {
         self.path.file_name()
     }
@@ -35,6 +44,24 @@ impl PathEntry {
     pub fn modified(&self) -> Option<SystemTime> {
         self.metadata.modified().ok()
     }
+
+    pub fn accessed(&self) -> Option<SystemTime> {
+        self.metadata.accessed().ok()
+    }
+
+    pub fn created(&self) -> Option<SystemTime> {
+        self.metadata.created().ok()
+    }
+
+    pub fn readonly(&self) -> bool {
+        self.metadata.permissions().readonly()
+    }
+
+    #[cfg(unix)]
+    pub fn mode(&self) -> u32 {
+        use std::os::unix::fs::MetadataExt;
+        self.metadata.mode()
+    }
 }
 
 impl PartialEq for PathEntry {
@@ -48,6 +75,25 @@ impl PartialEq for PathEntry {
 
 impl Eq for PathEntry {}
 
+/// Orders entries by path. Entries with the same path fall back to size,
+/// directory-ness and modification time, the fields [`PartialEq`] compares,
+/// so the ordering stays consistent with equality.
+impl Ord for PathEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.path
+            .cmp(&other.path)
+            .then_with(|| self.size().cmp(&other.size()))
+            .then_with(|| self.is_dir().cmp(&other.is_dir()))
+            .then_with(|| self.modified().cmp(&other.modified()))
+    }
+}
+
+impl PartialOrd for PathEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 pub(crate) fn path_entry_for(path: &Path) -> Option<PathEntry> {
     fs::symlink_metadata(path).ok().map(|metadata| PathEntry {
         path: path.to_path_buf(),
@@ -1,6 +1,13 @@
 use crate::{Result, Shell};
 
-use std::time::SystemTime;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    time::SystemTime,
+};
+
+use glob::Pattern;
 
 use super::entries::PathEntry;
 
@@ -28,6 +35,29 @@ pub fn filter_size(entries: Shell<Result<PathEntry>>, min_bytes: u64) -> Shell<R
     })
 }
 
+/// Keeps entries at or below the specified size (in bytes).
+pub fn filter_size_max(
+    entries: Shell<Result<PathEntry>>,
+    max_bytes: u64,
+) -> Shell<Result<PathEntry>> {
+    entries.filter_map(move |entry| match entry {
+        Ok(entry) => (entry.size() <= max_bytes).then_some(Ok(entry)),
+        Err(err) => Some(Err(err)),
+    })
+}
+
+/// Keeps entries whose size lies within `min..=max` bytes.
+pub fn filter_size_range(
+    entries: Shell<Result<PathEntry>>,
+    min: u64,
+    max: u64,
+) -> Shell<Result<PathEntry>> {
+    entries.filter_map(move |entry| match entry {
+        Ok(entry) => (min..=max).contains(&entry.size()).then_some(Ok(entry)),
+        Err(err) => Some(Err(err)),
+    })
+}
+
 /// Keeps entries modified at or after `since`.
 pub fn filter_modified_since(
     entries: Shell<Result<PathEntry>>,
@@ -42,3 +72,100 @@ pub fn filter_modified_since(
         Err(err) => Some(Err(err)),
     })
 }
+
+/// Keeps entries modified strictly before `before`.
+pub fn filter_modified_before(
+    entries: Shell<Result<PathEntry>>,
+    before: SystemTime,
+) -> Shell<Result<PathEntry>> {
+    entries.filter_map(move |entry| match entry {
+        Ok(entry) => entry
+            .modified()
+            .map(|time| time < before)
+            .unwrap_or(false)
+            .then_some(Ok(entry)),
+        Err(err) => Some(Err(err)),
+    })
+}
+
+/// Keeps entries whose modification time falls within `[start, end)`.
+pub fn filter_modified_between(
+    entries: Shell<Result<PathEntry>>,
+    start: SystemTime,
+    end: SystemTime,
+) -> Shell<Result<PathEntry>> {
+    entries.filter_map(move |entry| match entry {
+        Ok(entry) => entry
+            .modified()
+            .map(|time| start <= time && time < end)
+            .unwrap_or(false)
+            .then_some(Ok(entry)),
+        Err(err) => Some(Err(err)),
+    })
+}
+
+/// Keeps entries whose path matches a glob pattern, like [`watch_glob`](crate::watch_glob).
+///
+/// The pattern is compiled once and matched against the full entry path; as
+/// with `glob::Pattern`, `*` also matches `/`, so `*.txt` keeps every `.txt`
+/// file regardless of depth.
+pub fn filter_glob(
+    entries: Shell<Result<PathEntry>>,
+    pattern: impl AsRef<str>,
+) -> Result<Shell<Result<PathEntry>>> {
+    let pattern = Pattern::new(pattern.as_ref())?;
+    Ok(entries.filter(move |entry| match entry {
+        Ok(entry) => pattern.matches_path(&entry.path),
+        Err(_) => true,
+    }))
+}
+
+/// Keeps file entries with at least one line containing `needle`.
+///
+/// Files are read line by line and stop at the first hit, so large files are
+/// never loaded whole. Non-file entries are dropped; read errors are yielded
+/// as `Err`.
+pub fn filter_contains(
+    entries: Shell<Result<PathEntry>>,
+    needle: impl AsRef<str>,
+) -> Shell<Result<PathEntry>> {
+    let needle = needle.as_ref().to_string();
+    filter_lines(entries, move |line| line.contains(&needle))
+}
+
+/// Like [`filter_contains`], keeping files with a line matching `regex`.
+#[cfg(feature = "regex")]
+pub fn filter_matches(
+    entries: Shell<Result<PathEntry>>,
+    regex: regex::Regex,
+) -> Shell<Result<PathEntry>> {
+    filter_lines(entries, move |line| regex.is_match(line))
+}
+
+fn filter_lines<F>(entries: Shell<Result<PathEntry>>, mut keep: F) -> Shell<Result<PathEntry>>
+where
+    F: FnMut(&str) -> bool + 'static,
+{
+    entries.filter_map(move |entry| match entry {
+        Ok(entry) if entry.is_file() => match any_line(&entry.path, &mut keep) {
+            Ok(found) => found.then_some(Ok(entry)),
+            Err(err) => Some(Err(err)),
+        },
+        Ok(_) => None,
+        Err(err) => Some(Err(err)),
+    })
+}
+
+fn any_line(path: &Path, keep: &mut impl FnMut(&str) -> bool) -> Result<bool> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            return Ok(false);
+        }
+        if keep(&String::from_utf8_lossy(&buf)) {
+            return Ok(true);
+        }
+    }
+}
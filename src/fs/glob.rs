@@ -1,27 +1,163 @@
 use crate::{Result, Shell};
 
-use std::fs;
+use std::{collections::HashSet, fs, path::PathBuf};
 
-use glob::{Pattern, glob as glob_iter};
+use glob::{MatchOptions, Pattern, glob_with as glob_iter_with};
 
 use super::entries::PathEntry;
 
+/// Matching options for [`glob_with`] and [`glob_entries_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlobOptions {
+    /// Match letters regardless of case.
+    pub case_insensitive: bool,
+    /// Let wildcards match a leading `.` in file names.
+    pub include_hidden: bool,
+}
+
+impl Default for GlobOptions {
+    /// The options used by [`glob`]: case-sensitive, with dotfiles matched.
+    fn default() -> Self {
+        Self {
+            case_insensitive: false,
+            include_hidden: true,
+        }
+    }
+}
+
+impl From<GlobOptions> for MatchOptions {
+    fn from(options: GlobOptions) -> Self {
+        MatchOptions {
+            case_sensitive: !options.case_insensitive,
+            require_literal_leading_dot: !options.include_hidden,
+            ..MatchOptions::new()
+        }
+    }
+}
+
 /// Expands filesystem globs (e.g. `*.rs`) into a stream of paths.
-pub fn glob(pattern: impl AsRef<str>) -> Result<Shell<Result<std::path::PathBuf>>> {
-    let iter = glob_iter(pattern.as_ref())?;
-    Ok(Shell::new(Box::new(
-        iter.map(|entry| entry.map_err(Into::into)),
+///
+/// Brace alternations such as `src/{lib,main}.rs` are expanded first, like a
+/// shell would; paths matched by several alternatives are yielded once.
+pub fn glob(pattern: impl AsRef<str>) -> Result<Shell<Result<PathBuf>>> {
+    glob_with(pattern, GlobOptions::default())
+}
+
+/// Like [`glob`], with explicit case and hidden-file handling.
+pub fn glob_with(pattern: impl AsRef<str>, options: GlobOptions) -> Result<Shell<Result<PathBuf>>> {
+    let options = MatchOptions::from(options);
+    let mut patterns = expand_braces(pattern.as_ref());
+    if patterns.len() == 1 {
+        let paths = glob_iter_with(&patterns.remove(0), options)?;
+        return Ok(Shell::new(paths.map(|entry| entry.map_err(Into::into))));
+    }
+    let iters = patterns
+        .iter()
+        .map(|pattern| glob_iter_with(pattern, options))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    // Alternatives can overlap, so only brace expansions pay for deduplication.
+    let mut seen = HashSet::new();
+    Ok(Shell::new(iters.into_iter().flatten().filter_map(
+        move |entry| match entry {
+            Ok(path) => seen.insert(path.clone()).then_some(Ok(path)),
+            Err(err) => Some(Err(err.into())),
+        },
     )))
 }
 
+/// Counts the paths [`glob`] would yield without collecting them, failing on
+/// the first error encountered while expanding.
+pub fn glob_count(pattern: impl AsRef<str>) -> Result<usize> {
+    glob(pattern)?.try_fold(0, |count, path| path.map(|_| count + 1))
+}
+
 /// Expands globs while returning [`PathEntry`] metadata.
+///
+/// Supports the same brace alternations as [`glob`].
 pub fn glob_entries(pattern: impl AsRef<str>) -> Result<Shell<Result<PathEntry>>> {
-    let iter = glob_iter(pattern.as_ref())?;
-    Ok(Shell::new(Box::new(iter.map(|entry| {
+    glob_entries_with(pattern, GlobOptions::default())
+}
+
+/// Like [`glob_entries`], with explicit case and hidden-file handling.
+pub fn glob_entries_with(
+    pattern: impl AsRef<str>,
+    options: GlobOptions,
+) -> Result<Shell<Result<PathEntry>>> {
+    Ok(glob_with(pattern, options)?.map(|entry| {
         let path = entry?;
         let metadata = fs::metadata(&path)?;
         Ok(PathEntry { path, metadata })
-    }))))
+    }))
+}
+
+/// Expands `{a,b}` alternations into separate patterns, recursing into nested groups.
+///
+/// Groups without a top-level comma (`{}`, `{a}`) and unbalanced braces are kept
+/// literally, matching shell behaviour. Braces inside `[...]` classes are ignored.
+pub(super) fn expand_braces(pattern: &str) -> Vec<String> {
+    let bytes = pattern.as_bytes();
+    let mut i = 0;
+    let mut open = None;
+    let mut depth = 0;
+    let mut commas = Vec::new();
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' => {
+                if let Some(close) = class_end(bytes, i) {
+                    i = close;
+                }
+            }
+            b'{' => {
+                if depth == 0 {
+                    open = Some(i);
+                    commas.clear();
+                }
+                depth += 1;
+            }
+            b',' if depth == 1 => commas.push(i),
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0
+                    && let Some(start) = open.take()
+                {
+                    if commas.is_empty() {
+                        // Literal group: keep scanning inside it for nested alternations.
+                        i = start + 1;
+                        continue;
+                    }
+                    let (prefix, suffix) = (&pattern[..start], &pattern[i + 1..]);
+                    let mut bounds = vec![start];
+                    bounds.extend(&commas);
+                    bounds.push(i);
+                    return bounds
+                        .windows(2)
+                        .flat_map(|pair| {
+                            let alternative = &pattern[pair[0] + 1..pair[1]];
+                            expand_braces(&format!("{prefix}{alternative}{suffix}"))
+                        })
+                        .collect();
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    vec![pattern.to_string()]
+}
+
+/// Returns the index of the `]` closing the character class opened at `open`.
+fn class_end(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut i = open + 1;
+    if matches!(bytes.get(i), Some(b'!')) {
+        i += 1;
+    }
+    if matches!(bytes.get(i), Some(b']')) {
+        i += 1;
+    }
+    bytes[i.min(bytes.len())..]
+        .iter()
+        .position(|&b| b == b']')
+        .map(|offset| i + offset)
 }
 
 /// Cached glob results for reuse across multiple operations.
@@ -33,7 +169,7 @@ pub struct GlobCache {
 impl GlobCache {
     /// Resolves `pattern` immediately, storing `PathEntry` data in memory.
     pub fn new(pattern: impl AsRef<str>) -> Result<Self> {
-        let entries = glob_entries(pattern)?.collect::<Result<Vec<_>>>()?;
+        let entries = glob_entries(pattern)?.collect_results()?;
         Ok(Self { entries })
     }
 
@@ -1,15 +1,16 @@
 use crate::{Result, Shell};
 
 use std::{
-    env,
+    collections::VecDeque,
+    env, fmt,
     fs::{self, File, OpenOptions},
-    io::{self, BufRead, BufReader, Write},
+    io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
     process,
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use super::entries::PathEntry;
+use super::{entries::PathEntry, walk::WalkBuilder};
 
 /// Reads a UTF-8 file completely into a `String`.
 pub fn read_text(path: impl AsRef<Path>) -> Result<String> {
@@ -19,10 +20,138 @@ pub fn read_text(path: impl AsRef<Path>) -> Result<String> {
 /// Reads a file as a stream of lines.
 pub fn read_lines(path: impl AsRef<Path>) -> Result<Shell<Result<String>>> {
     let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    Ok(Shell::new(Box::new(
-        reader.lines().map(|line| line.map_err(Into::into)),
-    )))
+    Ok(Shell::lines_from_reader(BufReader::new(file)))
+}
+
+/// Returns the last `n` lines of a file.
+///
+/// Lines are streamed through a ring buffer, so memory is bounded by `n`
+/// rather than the file size. Trailing `\r` characters are trimmed like the
+/// command line helpers do.
+pub fn tail(path: impl AsRef<Path>, n: usize) -> Result<Vec<String>> {
+    let mut window = VecDeque::with_capacity(n);
+    for line in read_lines(path)? {
+        let line = line?;
+        if n == 0 {
+            continue;
+        }
+        if window.len() == n {
+            window.pop_front();
+        }
+        window.push_back(line.trim_end_matches('\r').to_string());
+    }
+    Ok(window.into())
+}
+
+/// Returns the last `n` bytes of a file (or all of it, if shorter).
+///
+/// Seeks from the end, so only the requested bytes are read.
+pub fn tail_bytes(path: impl AsRef<Path>, n: usize) -> Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(n as u64);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::with_capacity((len - start) as usize);
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads at most `count` lines starting at the zero-based `start_line`.
+///
+/// Skipped lines are scanned but never decoded, and reading stops once
+/// `count` lines have been yielded, so the rest of the file is not touched.
+/// `None` reads to the end.
+pub fn read_lines_range(
+    path: impl AsRef<Path>,
+    start_line: usize,
+    count: Option<usize>,
+) -> Result<Shell<Result<String>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    for _ in 0..start_line {
+        if reader.skip_until(b'\n')? == 0 {
+            break;
+        }
+    }
+    let lines = Shell::lines_from_reader(reader);
+    Ok(match count {
+        Some(count) => lines.take(count),
+        None => lines,
+    })
+}
+
+/// Reads a file's lines from last to first without loading it all at once.
+///
+/// The file is read backwards in chunks, so scanning stops cheaply once the
+/// consumer has seen enough. A final line without a trailing newline is
+/// yielded first; `\r\n` endings are stripped like [`read_lines`].
+pub fn read_lines_rev(path: impl AsRef<Path>) -> Result<Shell<Result<String>>> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    Ok(Shell::new(RevLinesIter {
+        file,
+        pos: len,
+        tail: Vec::new(),
+        started: false,
+        done: len == 0,
+    }))
+}
+
+/// Options for [`read_lines_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Capacity of the underlying `BufReader`, in bytes (at least 1).
+    pub buffer_capacity: usize,
+    /// Replace invalid UTF-8 with `U+FFFD` instead of yielding an error.
+    pub lossy: bool,
+}
+
+impl Default for ReadOptions {
+    /// The standard library's 8 KiB buffer, with strict UTF-8 decoding.
+    fn default() -> Self {
+        Self {
+            buffer_capacity: 8 * 1024,
+            lossy: false,
+        }
+    }
+}
+
+/// Like [`read_lines`], with a configurable buffer and optional lossy decoding.
+///
+/// Trailing `\r` characters are trimmed from each line, as the command line
+/// helpers do. The stream ends after the first read error.
+pub fn read_lines_with(
+    path: impl AsRef<Path>,
+    options: ReadOptions,
+) -> Result<Shell<Result<String>>> {
+    let file = File::open(path)?;
+    let mut reader = BufReader::with_capacity(options.buffer_capacity.max(1), file);
+    let mut done = false;
+    Ok(Shell::from_fn(move || {
+        if done {
+            return None;
+        }
+        let mut buf = Vec::new();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.last() == Some(&b'\n') {
+                    buf.pop();
+                }
+                while buf.last() == Some(&b'\r') {
+                    buf.pop();
+                }
+                if options.lossy {
+                    Some(Ok(String::from_utf8_lossy(&buf).into_owned()))
+                } else {
+                    Some(String::from_utf8(buf).map_err(Into::into))
+                }
+            }
+            Err(err) => {
+                done = true;
+                Some(Err(err.into()))
+            }
+        }
+    }))
 }
 
 /// Writes the provided text to the path (truncating existing file).
@@ -44,12 +173,178 @@ pub fn write_lines(
     Ok(())
 }
 
+/// Streams `Ok` lines from a shell into a file, each followed by `\n`.
+///
+/// The file is truncated first. Writing stops at, and returns, the first `Err`;
+/// lines before it remain on disk.
+pub fn write_lines_from(path: impl AsRef<Path>, lines: Shell<Result<String>>) -> Result<()> {
+    write_line_stream(File::create(path)?, lines)
+}
+
+/// Like [`write_lines_from`], but appends to the file, creating it if needed.
+pub fn append_lines_from(path: impl AsRef<Path>, lines: Shell<Result<String>>) -> Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    write_line_stream(file, lines)
+}
+
+fn write_line_stream(file: File, lines: Shell<Result<String>>) -> Result<()> {
+    let mut writer = BufWriter::new(file);
+    for line in lines {
+        writer.write_all(line?.as_bytes())?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
 /// Copies a file from `from` to `to`.
 pub fn copy_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
     let _ = fs::copy(from, to)?;
     Ok(())
 }
 
+/// Progress callback for [`copy_file_with`]: bytes copied so far and the total size.
+pub type CopyProgress = Box<dyn FnMut(u64, Option<u64>)>;
+
+/// Options for [`copy_file_with`].
+pub struct CopyOptions {
+    /// Replace an existing destination; when `false` the copy fails instead.
+    pub overwrite: bool,
+    /// Called after each chunk is written.
+    pub progress: Option<CopyProgress>,
+}
+
+impl Default for CopyOptions {
+    /// Overwrites the destination, like [`copy_file`], without progress reporting.
+    fn default() -> Self {
+        Self {
+            overwrite: true,
+            progress: None,
+        }
+    }
+}
+
+impl fmt::Debug for CopyOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyOptions")
+            .field("overwrite", &self.overwrite)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// Copies a file in chunks, reporting progress and optionally refusing to clobber.
+///
+/// Permissions are copied from the source, as [`copy_file`] does. Returns the
+/// number of bytes copied.
+pub fn copy_file_with(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    mut options: CopyOptions,
+) -> Result<u64> {
+    let to = to.as_ref();
+    let mut source = File::open(from)?;
+    let metadata = source.metadata()?;
+    let total = metadata.is_file().then_some(metadata.len());
+    let mut destination = if options.overwrite {
+        File::create(to)?
+    } else {
+        match OpenOptions::new().write(true).create_new(true).open(to) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("destination {} already exists", to.display()),
+                )
+                .into());
+            }
+            Err(err) => return Err(err.into()),
+        }
+    };
+    let mut buffer = vec![0; 64 * 1024];
+    let mut copied = 0;
+    loop {
+        let read = match source.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        destination.write_all(&buffer[..read])?;
+        copied += read as u64;
+        if let Some(progress) = options.progress.as_mut() {
+            progress(copied, total);
+        }
+    }
+    destination.set_permissions(metadata.permissions())?;
+    Ok(copied)
+}
+
+/// Copies `from` to `to` only when `to` is missing or has an older mtime.
+///
+/// The copy keeps the source mtime so repeated calls skip unchanged files.
+/// Returns whether a copy happened.
+pub fn copy_file_if_newer(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<bool> {
+    let from = from.as_ref();
+    let to = to.as_ref();
+    let source_modified = fs::metadata(from)?.modified()?;
+    match fs::metadata(to) {
+        Ok(meta) if meta.modified()? >= source_modified => return Ok(false),
+        Ok(_) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+    }
+    fs::copy(from, to)?;
+    OpenOptions::new()
+        .write(true)
+        .open(to)?
+        .set_modified(source_modified)?;
+    Ok(true)
+}
+
+/// Splits a file into numbered parts of at most `part_bytes` bytes each.
+///
+/// Parts are written next to `out_prefix` as `prefix.000`, `prefix.001`, ...
+/// and their paths are returned in order. An empty source produces no parts.
+pub fn split_file(
+    path: impl AsRef<Path>,
+    part_bytes: usize,
+    out_prefix: impl AsRef<Path>,
+) -> Result<Vec<PathBuf>> {
+    if part_bytes == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "part size must be greater than zero",
+        )
+        .into());
+    }
+    let mut reader = BufReader::new(File::open(path)?);
+    let prefix = out_prefix.as_ref().as_os_str();
+    let mut parts = Vec::new();
+    while !reader.fill_buf()?.is_empty() {
+        let mut name = prefix.to_os_string();
+        name.push(format!(".{:03}", parts.len()));
+        let part = PathBuf::from(name);
+        let mut file = File::create(&part)?;
+        io::copy(&mut (&mut reader).take(part_bytes as u64), &mut file)?;
+        parts.push(part);
+    }
+    Ok(parts)
+}
+
+/// Concatenates `parts` in order into `out`, replacing any existing file.
+pub fn join_files<P, I>(parts: I, out: impl AsRef<Path>) -> Result<()>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = P>,
+{
+    let mut file = File::create(out)?;
+    for part in parts {
+        io::copy(&mut File::open(part)?, &mut file)?;
+    }
+    Ok(())
+}
+
 /// Appends bytes to the end of the given file, creating it if needed.
 pub fn append_text(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
     let mut file = OpenOptions::new().create(true).append(true).open(path)?;
@@ -70,6 +365,21 @@ where
     Ok(Shell::new(Box::new(CatIter::new(files))))
 }
 
+/// Concatenates every file matching `pattern`, in sorted path order.
+///
+/// The glob is resolved up front and any resolution error is returned
+/// immediately; directories among the matches are skipped. Files are then
+/// opened lazily as in [`cat`], so open errors surface mid-stream.
+pub fn cat_glob(pattern: impl AsRef<str>) -> Result<Shell<Result<String>>> {
+    let mut files = super::glob::glob(pattern)?
+        .collect_results()?
+        .into_iter()
+        .filter(|path| !path.is_dir())
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(Shell::new(Box::new(CatIter::new(files))))
+}
+
 /// Creates a directory and all missing parents.
 pub fn mkdir_all(path: impl AsRef<Path>) -> Result<()> {
     fs::create_dir_all(path)?;
@@ -93,6 +403,61 @@ pub fn rm(path: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
+/// Moves a file or directory to the OS trash instead of deleting it.
+///
+/// Like [`rm`], a missing path is not an error. Supported on Windows, macOS,
+/// and freedesktop.org systems (Linux and the BSDs); elsewhere this returns
+/// an `Unsupported` error and leaves the path untouched. Requires
+/// `--features trash`.
+#[cfg(feature = "trash")]
+pub fn rm_to_trash(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    match fs::symlink_metadata(path) {
+        Ok(_) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    }
+    move_to_trash(path)
+}
+
+#[cfg(all(
+    feature = "trash",
+    any(
+        windows,
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    )
+))]
+fn move_to_trash(path: &Path) -> Result<()> {
+    trash::delete(path).map_err(|err| {
+        io::Error::other(format!("failed to trash {}: {err}", path.display())).into()
+    })
+}
+
+#[cfg(all(
+    feature = "trash",
+    not(any(
+        windows,
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))
+))]
+fn move_to_trash(path: &Path) -> Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("no trash support on this platform for {}", path.display()),
+    )
+    .into())
+}
+
 /// Recursively copies a directory tree.
 pub fn copy_dir(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
     let from = from.as_ref();
@@ -118,6 +483,102 @@ pub fn copy_dir(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
+/// Options for [`copy_dir_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyDirOptions {
+    /// Copy what symlinks point at instead of recreating the links themselves.
+    pub follow_symlinks: bool,
+}
+
+/// Recursively copies a directory tree with explicit symlink handling.
+///
+/// When `follow_symlinks` is `false`, every symlink in the source is recreated
+/// at the destination with the same (possibly relative) target, and symlinked
+/// directories are not descended into. When `true`, linked files and
+/// directories are copied as regular ones; a link back to one of its own
+/// ancestors is created as an empty directory instead of being recursed into.
+pub fn copy_dir_with(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    options: CopyDirOptions,
+) -> Result<()> {
+    let from = from.as_ref();
+    let to = to.as_ref();
+    mkdir_all(to)?;
+    let entries = WalkBuilder::new(from)
+        .follow_symlinks(options.follow_symlinks)
+        .build()?;
+    for entry in entries {
+        let entry = entry?;
+        let relative = entry.path.strip_prefix(from).unwrap_or(&entry.path);
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let target = to.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if entry.metadata.file_type().is_symlink() {
+            copy_symlink(&entry.path, &target)?;
+        } else {
+            fs::copy(&entry.path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_symlink(link: &Path, target: &Path) -> Result<()> {
+    let destination = fs::read_link(link)?;
+    match fs::symlink_metadata(target) {
+        Ok(meta) if !meta.is_dir() => fs::remove_file(target)?,
+        _ => {}
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&destination, target)?;
+    #[cfg(windows)]
+    {
+        if fs::metadata(link).is_ok_and(|meta| meta.is_dir()) {
+            std::os::windows::fs::symlink_dir(&destination, target)?;
+        } else {
+            std::os::windows::fs::symlink_file(&destination, target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Recursively copies a directory tree, skipping files that are up to date.
+///
+/// Uses [`copy_file_if_newer`] per file and returns how many files were copied.
+pub fn copy_dir_incremental(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<usize> {
+    let from = from.as_ref();
+    let to = to.as_ref();
+    mkdir_all(to)?;
+    let mut copied = 0;
+    for path in super::walk::walk(from)? {
+        let path = path?;
+        let relative = path.strip_prefix(from).unwrap_or(&path);
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let target = to.join(relative);
+        if path.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if copy_file_if_newer(&path, &target)? {
+                copied += 1;
+            }
+        }
+    }
+    Ok(copied)
+}
+
 /// Moves a file or directory, falling back to copy/remove when needed.
 pub fn move_path(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
     let from = from.as_ref();
@@ -166,32 +627,101 @@ pub fn copy_entries(
 
 /// Creates a uniquely named temporary file and returns its path.
 pub fn temp_file(prefix: impl AsRef<str>) -> Result<PathBuf> {
-    let prefix = prefix.as_ref();
-    let base = env::temp_dir();
+    let (path, _) = create_unique_file(&env::temp_dir(), prefix.as_ref())?;
+    Ok(path)
+}
+
+/// Creates a uniquely named, empty directory under the system temp dir.
+///
+/// The caller owns the directory; remove it with [`rm`] when done.
+pub fn temp_dir(prefix: impl AsRef<str>) -> Result<PathBuf> {
+    let (path, ()) = create_unique(&env::temp_dir(), prefix.as_ref(), "", |candidate| {
+        fs::create_dir(candidate)
+    })?;
+    Ok(path)
+}
+
+/// Replaces `path` with `contents` without ever exposing a partial file.
+///
+/// The data is written to a temporary sibling of `path`, flushed to disk, and
+/// then renamed over the target, so readers see either the old or the new
+/// contents. The rename is only atomic within one filesystem, which holds
+/// because the temporary file lives in the target's directory; if `path` is a
+/// symlink, the link itself is replaced. On Windows, `fs::rename` replaces an
+/// existing target.
+pub fn write_text_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = path.as_ref();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "atomic write target has no file name",
+        )
+    })?;
+    let prefix = format!(".{}", name.to_string_lossy());
+    let (temp, mut file) = create_unique_file(dir, &prefix)?;
+    let written = file
+        .write_all(contents.as_ref())
+        .and_then(|()| file.sync_all());
+    drop(file);
+    if let Err(err) = written.and_then(|()| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+fn create_unique_file(base: &Path, prefix: &str) -> Result<(PathBuf, File)> {
+    create_unique(base, prefix, ".tmp", |candidate| {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(candidate)
+    })
+}
+
+fn create_unique<T>(
+    base: &Path,
+    prefix: &str,
+    suffix: &str,
+    mut create: impl FnMut(&Path) -> io::Result<T>,
+) -> Result<(PathBuf, T)> {
     let pid = process::id();
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis();
     for attempt in 0..100 {
-        let candidate = base.join(format!("{prefix}-{pid}-{now}-{attempt}.tmp"));
-        match OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(&candidate)
-        {
-            Ok(_) => return Ok(candidate),
+        let candidate = base.join(format!("{prefix}-{pid}-{now}-{attempt}{suffix}"));
+        match create(&candidate) {
+            Ok(created) => return Ok((candidate, created)),
             Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
             Err(err) => return Err(err.into()),
         }
     }
     Err(io::Error::new(
         io::ErrorKind::AlreadyExists,
-        "failed to allocate temporary file",
+        "failed to allocate a unique temporary path",
     )
     .into())
 }
 
+/// Creates a temporary file, passes its path to `f`, then removes it.
+///
+/// The file is removed even when `f` fails; an error from `f` takes precedence
+/// over a cleanup failure.
+pub fn with_temp_file<R>(prefix: &str, f: impl FnOnce(&Path) -> Result<R>) -> Result<R> {
+    let path = temp_file(prefix)?;
+    let result = f(&path);
+    let cleanup = rm(&path);
+    let value = result?;
+    cleanup?;
+    Ok(value)
+}
+
 struct CatIter {
     files: Vec<PathBuf>,
     idx: usize,
@@ -248,3 +778,66 @@ impl Iterator for CatIter {
         }
     }
 }
+
+const REV_CHUNK: usize = 8 * 1024;
+
+struct RevLinesIter {
+    file: File,
+    pos: u64,
+    tail: Vec<u8>,
+    started: bool,
+    done: bool,
+}
+
+impl RevLinesIter {
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let size = (REV_CHUNK.max(self.tail.len()) as u64).min(self.pos);
+        self.pos -= size;
+        self.file.seek(SeekFrom::Start(self.pos))?;
+        let mut chunk = vec![0; size as usize];
+        self.file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&self.tail);
+        self.tail = chunk;
+        if !self.started {
+            self.started = true;
+            if self.tail.last() == Some(&b'\n') {
+                self.tail.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(mut line: Vec<u8>) -> Result<String> {
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(String::from_utf8(line)?)
+    }
+}
+
+impl Iterator for RevLinesIter {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if self.started {
+                if let Some(idx) = self.tail.iter().rposition(|&byte| byte == b'\n') {
+                    let line = self.tail.split_off(idx + 1);
+                    self.tail.pop();
+                    return Some(Self::decode(line));
+                }
+                if self.pos == 0 {
+                    self.done = true;
+                    return Some(Self::decode(std::mem::take(&mut self.tail)));
+                }
+            }
+            if let Err(err) = self.read_chunk() {
+                self.done = true;
+                return Some(Err(err.into()));
+            }
+        }
+    }
+}
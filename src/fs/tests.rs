@@ -1,6 +1,6 @@
 use super::*;
 use crate::Shell;
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 use tempfile::tempdir;
 
 #[test]
@@ -275,3 +275,733 @@ where
         }
     }
 }
+
+#[test]
+fn split_and_join_roundtrip() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let source = dir.path().join("source.bin");
+    let data: Vec<u8> = (0..2500u32).map(|n| (n % 251) as u8).collect();
+    write_text(&source, &data)?;
+
+    let parts = split_file(&source, 1000, dir.path().join("chunk"))?;
+    let names: Vec<_> = parts
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, vec!["chunk.000", "chunk.001", "chunk.002"]);
+    assert_eq!(std::fs::metadata(&parts[2])?.len(), 500);
+
+    let joined = dir.path().join("joined.bin");
+    join_files(&parts, &joined)?;
+    assert_eq!(std::fs::read(&joined)?, data);
+
+    assert!(split_file(&source, 0, dir.path().join("zero")).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn walk_filter_parallel_matches_sequential() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let nested = dir.path().join("nested");
+    mkdir_all(&nested)?;
+    for idx in 0..8 {
+        let parent = if idx % 2 == 0 { dir.path() } else { &nested };
+        write_text(parent.join(format!("file-{idx}.txt")), "x".repeat(idx))?;
+    }
+    fn slow_predicate(entry: &PathEntry) -> bool {
+        std::thread::sleep(Duration::from_millis(5));
+        entry.is_file() && entry.size() >= 3
+    }
+
+    let parallel = walk_filter_parallel(dir.path(), slow_predicate)?;
+    let sequential = walk_filter(dir.path(), slow_predicate)?.collect::<crate::Result<Vec<_>>>()?;
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel.len(), 5);
+    Ok(())
+}
+
+#[test]
+fn copy_dir_incremental_skips_unchanged_files() -> crate::Result<()> {
+    let src = tempdir()?;
+    let dest = tempdir()?;
+    mkdir_all(src.path().join("nested"))?;
+    write_text(src.path().join("a.txt"), "a")?;
+    write_text(src.path().join("nested/b.txt"), "b")?;
+
+    assert_eq!(copy_dir_incremental(src.path(), dest.path())?, 2);
+    assert_eq!(read_text(dest.path().join("nested/b.txt"))?, "b");
+    assert_eq!(copy_dir_incremental(src.path(), dest.path())?, 0);
+
+    let a = src.path().join("a.txt");
+    write_text(&a, "changed")?;
+    let later = std::time::SystemTime::now() + Duration::from_secs(5);
+    std::fs::File::options()
+        .write(true)
+        .open(&a)?
+        .set_modified(later)?;
+    assert!(copy_file_if_newer(&a, dest.path().join("a.txt"))?);
+    assert_eq!(read_text(dest.path().join("a.txt"))?, "changed");
+    Ok(())
+}
+
+#[test]
+fn expand_braces_handles_nested_and_literal_groups() {
+    use super::glob::expand_braces;
+
+    assert_eq!(
+        expand_braces("src/{lib,main}.rs"),
+        vec!["src/lib.rs", "src/main.rs"]
+    );
+    assert_eq!(expand_braces("{a,{b,c}d}x"), vec!["ax", "bdx", "cdx"]);
+    assert_eq!(expand_braces("{a,b}{1,2}"), vec!["a1", "a2", "b1", "b2"]);
+    assert_eq!(expand_braces("file{,.bak}"), vec!["file", "file.bak"]);
+    assert_eq!(expand_braces("{}"), vec!["{}"]);
+    assert_eq!(expand_braces("{a}"), vec!["{a}"]);
+    assert_eq!(expand_braces("{x{a,b}}"), vec!["{xa}", "{xb}"]);
+    assert_eq!(expand_braces("[{,]{a,b"), vec!["[{,]{a,b"]);
+}
+
+#[test]
+fn glob_expands_braces_and_dedups() -> crate::Result<()> {
+    let dir = tempdir()?;
+    for name in ["lib.rs", "main.rs", "other.rs"] {
+        write_text(dir.path().join(name), "x")?;
+    }
+    let root = dir.path().to_string_lossy();
+    let mut names: Vec<_> = glob(format!("{root}/{{lib,main,l*}}.rs"))?
+        .collect_results()?
+        .into_iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["lib.rs", "main.rs"]);
+
+    let entries = glob_entries(format!("{root}/{{other,main}}.rs"))?.collect_results()?;
+    assert_eq!(entries.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn glob_with_controls_case_and_hidden_files() -> crate::Result<()> {
+    let dir = tempdir()?;
+    for name in ["README.MD", "notes.md", ".hidden.md"] {
+        write_text(dir.path().join(name), "x")?;
+    }
+    let pattern = format!("{}/*.md", dir.path().to_string_lossy());
+    let names = |paths: Vec<PathBuf>| {
+        let mut names: Vec<_> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    };
+
+    let default = names(glob(&pattern)?.collect_results()?);
+    assert_eq!(default, vec![".hidden.md", "notes.md"]);
+
+    let options = GlobOptions {
+        case_insensitive: true,
+        include_hidden: false,
+    };
+    let relaxed = names(glob_with(&pattern, options)?.collect_results()?);
+    assert_eq!(relaxed, vec!["README.MD", "notes.md"]);
+
+    let entries = glob_entries_with(&pattern, options)?.collect_results()?;
+    assert_eq!(entries.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn walk_builder_prunes_excluded_directories() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path();
+    mkdir_all(root.join("src/nested"))?;
+    mkdir_all(root.join("target/debug"))?;
+    mkdir_all(root.join("crates/core/target"))?;
+    write_text(root.join("src/lib.rs"), "x")?;
+    write_text(root.join("src/nested/deep.rs"), "x")?;
+    write_text(root.join("target/debug/app"), "x")?;
+    write_text(root.join("crates/core/target/out"), "x")?;
+
+    let relative = |entries: Vec<PathEntry>| -> Vec<PathBuf> {
+        let mut paths: Vec<_> = entries
+            .into_iter()
+            .map(|entry| entry.path.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        paths.sort();
+        paths
+    };
+
+    let walked = WalkBuilder::new(root)
+        .exclude("target")
+        .build()?
+        .collect_results()?;
+    let walked = relative(walked);
+    assert!(walked.iter().all(|path| !path.starts_with("target")));
+    assert!(!walked.contains(&PathBuf::from("crates/core/target/out")));
+    assert!(walked.contains(&PathBuf::from("src/nested/deep.rs")));
+
+    let shallow = WalkBuilder::new(root)
+        .exclude("crates")
+        .max_depth(1)
+        .build()?
+        .collect_results()?;
+    assert_eq!(
+        relative(shallow),
+        vec![PathBuf::new(), "src".into(), "target".into()]
+    );
+
+    assert!(WalkBuilder::new(root).exclude("[").build().is_err());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn walk_builder_follows_symlinks_without_cycling() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let real = dir.path().join("real");
+    mkdir_all(&real)?;
+    write_text(real.join("file.txt"), "x")?;
+    std::os::unix::fs::symlink(&real, dir.path().join("link"))?;
+    std::os::unix::fs::symlink(dir.path(), real.join("loop"))?;
+
+    let plain = WalkBuilder::new(dir.path()).build()?.collect_results()?;
+    assert_eq!(
+        plain
+            .iter()
+            .filter(|entry| entry.path.ends_with("file.txt"))
+            .count(),
+        1
+    );
+
+    let followed = WalkBuilder::new(dir.path())
+        .follow_symlinks(true)
+        .build()?
+        .collect_results()?;
+    let mut files: Vec<_> = followed
+        .iter()
+        .filter(|entry| entry.path.ends_with("file.txt"))
+        .map(|entry| entry.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            PathBuf::from("link/file.txt"),
+            PathBuf::from("real/file.txt")
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn walk_depth_stops_below_limit() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path();
+    mkdir_all(root.join("a/b/c"))?;
+    write_text(root.join("top.txt"), "x")?;
+    write_text(root.join("a/mid.txt"), "x")?;
+    write_text(root.join("a/b/c/deep.txt"), "x")?;
+
+    let mut paths: Vec<PathBuf> = walk_depth(root, 1)?
+        .collect_results()?
+        .into_iter()
+        .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+        .collect();
+    paths.sort();
+    assert_eq!(paths, vec![PathBuf::new(), "a".into(), "top.txt".into()]);
+
+    let detailed = walk_detailed_depth(root, 0)?.collect_results()?;
+    assert_eq!(detailed.len(), 1);
+    assert_eq!(detailed[0].path, root);
+    assert_eq!(walk_detailed_depth(root, 4)?.count(), walk(root)?.count());
+    Ok(())
+}
+
+#[test]
+fn walk_bfs_yields_shallow_entries_first() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path();
+    mkdir_all(root.join("a/b"))?;
+    mkdir_all(root.join("z"))?;
+    write_text(root.join("a/b/deep.txt"), "x")?;
+    write_text(root.join("a/mid.txt"), "x")?;
+    write_text(root.join("top.txt"), "x")?;
+
+    let depths: Vec<usize> = walk_bfs(root)?
+        .collect_results()?
+        .into_iter()
+        .map(|path| path.strip_prefix(root).unwrap().components().count())
+        .collect();
+    assert_eq!(depths, vec![0, 1, 1, 1, 2, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn walk_errors_name_the_failing_path() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let missing = dir.path().join("missing/subdir");
+    let err = walk_detailed(&missing)?.collect_results().unwrap_err();
+    match &err {
+        crate::Error::Walk { path, source } => {
+            assert_eq!(path, &missing);
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert!(err.to_string().contains("missing"));
+    assert!(matches!(
+        walk(&missing)?.collect_results(),
+        Err(crate::Error::Walk { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn with_temp_file_cleans_up_after_closure() -> crate::Result<()> {
+    let mut seen = PathBuf::new();
+    let text = with_temp_file("qshr-scoped", |path| {
+        seen = path.to_path_buf();
+        write_text(path, "scoped")?;
+        read_text(path)
+    })?;
+    assert_eq!(text, "scoped");
+    assert!(!seen.exists());
+
+    let mut failed = PathBuf::new();
+    let result: crate::Result<()> = with_temp_file("qshr-scoped", |path| {
+        failed = path.to_path_buf();
+        Err(crate::Error::Io(std::io::Error::other("boom")))
+    });
+    assert!(result.is_err());
+    assert!(!failed.exists());
+    Ok(())
+}
+
+#[test]
+fn read_lines_with_lossy_decoding() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("log.txt");
+    std::fs::write(&path, b"ok\r\nbad \xff byte\nlast")?;
+
+    let lossy = ReadOptions {
+        buffer_capacity: 4,
+        lossy: true,
+    };
+    let lines = read_lines_with(&path, lossy)?.collect_results()?;
+    assert_eq!(lines, vec!["ok", "bad \u{fffd} byte", "last"]);
+
+    let strict = read_lines_with(&path, ReadOptions::default())?.collect_results();
+    assert!(matches!(strict, Err(crate::Error::Utf8(_))));
+    Ok(())
+}
+
+#[test]
+fn write_lines_from_streams_shell() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("out.txt");
+    let lines = Shell::from_iter((1..=3).map(|n| Ok(format!("line {n}"))));
+    write_lines_from(&path, lines)?;
+    append_lines_from(&path, Shell::from_iter([Ok("tail".to_string())]))?;
+    let read = read_lines(&path)?.collect_results()?;
+    assert_eq!(read, vec!["line 1", "line 2", "line 3", "tail"]);
+
+    let failing = Shell::from_iter([
+        Ok("kept".to_string()),
+        Err(crate::Error::Io(std::io::Error::other("stop"))),
+        Ok("dropped".to_string()),
+    ]);
+    assert!(write_lines_from(&path, failing).is_err());
+    assert_eq!(read_text(&path)?, "kept\n");
+    Ok(())
+}
+
+#[test]
+fn write_text_atomic_replaces_contents() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("config.toml");
+    write_text(&path, "old = true\n")?;
+    let contents = "key = \"value\"\n".repeat(1000);
+    write_text_atomic(&path, &contents)?;
+    assert_eq!(read_text(&path)?, contents);
+
+    let fresh = dir.path().join("fresh.txt");
+    write_text_atomic(&fresh, "new")?;
+    assert_eq!(read_text(&fresh)?, "new");
+
+    let leftovers = ls(dir.path())?.collect_results()?;
+    assert_eq!(leftovers.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn copy_file_with_reports_progress() -> crate::Result<()> {
+    use std::{cell::RefCell, rc::Rc};
+
+    let dir = tempdir()?;
+    let from = dir.path().join("big.bin");
+    let to = dir.path().join("copy.bin");
+    let data = vec![7u8; 200 * 1024];
+    std::fs::write(&from, &data)?;
+
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let recorder = calls.clone();
+    let options = CopyOptions {
+        overwrite: false,
+        progress: Some(Box::new(move |copied, total| {
+            recorder.borrow_mut().push((copied, total))
+        })),
+    };
+    assert_eq!(copy_file_with(&from, &to, options)?, data.len() as u64);
+    assert_eq!(std::fs::read(&to)?, data);
+
+    let calls = calls.borrow();
+    assert!(calls.len() > 1);
+    assert_eq!(
+        calls.last(),
+        Some(&(data.len() as u64, Some(data.len() as u64)))
+    );
+
+    let refused = CopyOptions {
+        overwrite: false,
+        progress: None,
+    };
+    let err = copy_file_with(&from, &to, refused).unwrap_err();
+    assert!(err.to_string().contains("already exists"));
+    copy_file_with(&from, &to, CopyOptions::default())?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn copy_dir_with_preserves_symlinks() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let src = dir.path().join("src");
+    mkdir_all(src.join("nested"))?;
+    write_text(src.join("real.txt"), "real")?;
+    write_text(src.join("nested/inner.txt"), "inner")?;
+    std::os::unix::fs::symlink("real.txt", src.join("link.txt"))?;
+    std::os::unix::fs::symlink("nested", src.join("dir-link"))?;
+
+    let kept = dir.path().join("kept");
+    copy_dir_with(&src, &kept, CopyDirOptions::default())?;
+    let link = kept.join("link.txt");
+    assert!(std::fs::symlink_metadata(&link)?.file_type().is_symlink());
+    assert_eq!(std::fs::read_link(&link)?, PathBuf::from("real.txt"));
+    assert_eq!(read_text(&link)?, "real");
+    assert!(
+        std::fs::symlink_metadata(kept.join("dir-link"))?
+            .file_type()
+            .is_symlink()
+    );
+
+    let followed = dir.path().join("followed");
+    copy_dir_with(
+        &src,
+        &followed,
+        CopyDirOptions {
+            follow_symlinks: true,
+        },
+    )?;
+    let copied = std::fs::symlink_metadata(followed.join("link.txt"))?;
+    assert!(copied.file_type().is_file());
+    assert!(followed.join("dir-link").is_dir());
+    assert_eq!(read_text(followed.join("nested/inner.txt"))?, "inner");
+    let through_link = followed.join("dir-link/inner.txt");
+    assert!(
+        std::fs::symlink_metadata(&through_link)?
+            .file_type()
+            .is_file()
+    );
+    assert_eq!(read_text(&through_link)?, "inner");
+    Ok(())
+}
+
+#[cfg(feature = "trash")]
+#[test]
+fn rm_to_trash_ignores_missing_paths() -> crate::Result<()> {
+    let dir = tempdir()?;
+    rm_to_trash(dir.path().join("never-created"))?;
+    Ok(())
+}
+
+#[test]
+fn read_lines_rev_yields_last_line_first() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("log.txt");
+    let long = "x".repeat(20_000);
+    write_text(&path, format!("first\r\n\n{long}\nlast"))?;
+    let lines = read_lines_rev(&path)?.collect_results()?;
+    assert_eq!(
+        lines,
+        vec!["last".to_string(), long, String::new(), "first".into()]
+    );
+
+    write_text(&path, "one\ntwo\n")?;
+    let lines = read_lines_rev(&path)?.collect_results()?;
+    assert_eq!(lines, vec!["two", "one"]);
+
+    let many: Vec<String> = (0..5000).map(|n| format!("line {n}")).collect();
+    write_lines(&path, &many)?;
+    let mut reversed = read_lines_rev(&path)?.collect_results()?;
+    reversed.reverse();
+    assert_eq!(reversed, many);
+
+    write_text(&path, "")?;
+    assert_eq!(read_lines_rev(&path)?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn temp_dir_creates_writable_directory() -> crate::Result<()> {
+    let first = temp_dir("qshr-scratch")?;
+    let second = temp_dir("qshr-scratch")?;
+    assert_ne!(first, second);
+    assert!(first.is_dir());
+    assert_eq!(ls(&first)?.count(), 0);
+    write_text(first.join("note.txt"), "hi")?;
+    assert_eq!(read_text(first.join("note.txt"))?, "hi");
+    rm(&first)?;
+    rm(&second)?;
+    assert!(!first.exists());
+    Ok(())
+}
+
+#[test]
+fn cat_glob_concatenates_sorted_matches() -> crate::Result<()> {
+    let dir = tempdir()?;
+    write_text(dir.path().join("b.log"), "b1\nb2\n")?;
+    write_text(dir.path().join("a.log"), "a1\n")?;
+    write_text(dir.path().join("skip.txt"), "nope\n")?;
+    mkdir_all(dir.path().join("dir.log"))?;
+
+    let pattern = format!("{}/*.log", dir.path().display());
+    let lines = cat_glob(&pattern)?.collect_results()?;
+    assert_eq!(lines, vec!["a1", "b1", "b2"]);
+    assert!(cat_glob("[").is_err());
+    Ok(())
+}
+
+#[test]
+fn read_lines_range_extracts_window() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("big.txt");
+    write_lines(&path, (0..5000).map(|n| format!("line {n}")))?;
+
+    let window = read_lines_range(&path, 1000, Some(10))?.collect_results()?;
+    let expected: Vec<String> = (1000..1010).map(|n| format!("line {n}")).collect();
+    assert_eq!(window, expected);
+
+    let rest = read_lines_range(&path, 4998, None)?.collect_results()?;
+    assert_eq!(rest, vec!["line 4998", "line 4999"]);
+    assert_eq!(read_lines_range(&path, 10_000, Some(5))?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn tail_keeps_last_lines() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("app.log");
+    write_text(&path, "one\r\ntwo\nthree\nfour\n")?;
+    assert_eq!(tail(&path, 2)?, vec!["three", "four"]);
+    assert_eq!(tail(&path, 10)?, vec!["one", "two", "three", "four"]);
+    assert!(tail(&path, 0)?.is_empty());
+
+    assert_eq!(tail_bytes(&path, 5)?, b"four\n");
+    assert_eq!(tail_bytes(&path, 100)?, std::fs::read(&path)?);
+    assert!(tail(dir.path().join("missing.log"), 1).is_err());
+    Ok(())
+}
+
+#[test]
+fn filter_glob_keeps_matching_entries() -> crate::Result<()> {
+    let dir = tempdir()?;
+    mkdir_all(dir.path().join("nested"))?;
+    write_text(dir.path().join("a.txt"), "a")?;
+    write_text(dir.path().join("b.rs"), "b")?;
+    write_text(dir.path().join("nested/c.txt"), "c")?;
+
+    let mut names: Vec<_> = filter_glob(walk_detailed(dir.path())?, "*.txt")?
+        .collect_results()?
+        .into_iter()
+        .map(|entry| entry.path.file_name().unwrap().to_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["a.txt", "c.txt"]);
+
+    let errors = Shell::from_iter([Err(crate::Error::Io(std::io::Error::other("walk")))]);
+    assert_eq!(filter_glob(errors, "*.txt")?.count(), 1);
+    assert!(filter_glob(Shell::empty(), "[").is_err());
+    Ok(())
+}
+
+#[test]
+fn filter_modified_before_and_between_use_mtime() -> crate::Result<()> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let dir = tempdir()?;
+    let base = UNIX_EPOCH + Duration::from_secs(1_000_000);
+    for (name, offset) in [("old.txt", 0), ("mid.txt", 100), ("new.txt", 200)] {
+        let path = dir.path().join(name);
+        write_text(&path, name)?;
+        std::fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(base + Duration::from_secs(offset))?;
+    }
+    let names = |entries: Shell<crate::Result<PathEntry>>| -> crate::Result<Vec<String>> {
+        let mut names: Vec<_> = entries
+            .collect_results()?
+            .into_iter()
+            .map(|entry| {
+                entry
+                    .path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        names.sort();
+        Ok(names)
+    };
+
+    let before = filter_modified_before(ls_detailed(dir.path())?, base + Duration::from_secs(100));
+    assert_eq!(names(before)?, vec!["old.txt"]);
+
+    let between = filter_modified_between(
+        ls_detailed(dir.path())?,
+        base + Duration::from_secs(100),
+        base + Duration::from_secs(200),
+    );
+    assert_eq!(names(between)?, vec!["mid.txt"]);
+
+    let all = filter_modified_before(ls_detailed(dir.path())?, SystemTime::now());
+    assert_eq!(names(all)?.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn filter_size_max_and_range_include_boundaries() -> crate::Result<()> {
+    let dir = tempdir()?;
+    for (name, len) in [("empty", 0), ("four", 4), ("eight", 8), ("twelve", 12)] {
+        write_text(dir.path().join(name), "x".repeat(len))?;
+    }
+    let sizes = |entries: Shell<crate::Result<PathEntry>>| -> crate::Result<Vec<u64>> {
+        let mut sizes: Vec<_> = entries
+            .collect_results()?
+            .iter()
+            .map(PathEntry::size)
+            .collect();
+        sizes.sort();
+        Ok(sizes)
+    };
+
+    assert_eq!(
+        sizes(filter_size_max(ls_detailed(dir.path())?, 4))?,
+        vec![0, 4]
+    );
+    assert_eq!(
+        sizes(filter_size_range(ls_detailed(dir.path())?, 4, 8))?,
+        vec![4, 8]
+    );
+    assert!(sizes(filter_size_range(ls_detailed(dir.path())?, 9, 11))?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn filter_contains_keeps_files_with_needle() -> crate::Result<()> {
+    let dir = tempdir()?;
+    mkdir_all(dir.path().join("sub"))?;
+    write_text(
+        dir.path().join("todo.rs"),
+        "fn main() {}\n// TODO: tidy up\n",
+    )?;
+    write_text(dir.path().join("done.rs"), "fn main() {}\n")?;
+
+    let kept = filter_contains(ls_detailed(dir.path())?, "TODO").collect_results()?;
+    assert_eq!(kept.len(), 1);
+    assert!(kept[0].path.ends_with("todo.rs"));
+
+    #[cfg(feature = "regex")]
+    {
+        let regex = regex::Regex::new(r"^fn \w+\(").unwrap();
+        let kept = filter_matches(ls_detailed(dir.path())?, regex).collect_results()?;
+        assert_eq!(kept.len(), 2);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn path_entry_reports_symlinks() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let target = dir.path().join("target.txt");
+    let link = dir.path().join("link.txt");
+    write_text(&target, "data")?;
+    std::os::unix::fs::symlink(&target, &link)?;
+
+    let entries = ls_detailed(dir.path())?.collect_results()?;
+    let link_entry = entries.iter().find(|entry| entry.path == link).unwrap();
+    assert!(link_entry.is_symlink());
+    assert_eq!(link_entry.symlink_target(), Some(target.clone()));
+
+    let file_entry = entries.iter().find(|entry| entry.path == target).unwrap();
+    assert!(!file_entry.is_symlink());
+    assert_eq!(file_entry.symlink_target(), None);
+    Ok(())
+}
+
+#[test]
+fn glob_count_counts_matches() -> crate::Result<()> {
+    let dir = tempdir()?;
+    for name in ["a.txt", "b.txt", "c.txt", "d.rs"] {
+        write_text(dir.path().join(name), name)?;
+    }
+    let pattern = dir.path().join("*.txt");
+    assert_eq!(glob_count(pattern.to_string_lossy())?, 3);
+    let none = dir.path().join("*.md");
+    assert_eq!(glob_count(none.to_string_lossy())?, 0);
+    Ok(())
+}
+
+#[test]
+fn path_entry_exposes_times_and_permissions() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let file = dir.path().join("audit.txt");
+    write_text(&file, "audit")?;
+    let mut permissions = std::fs::metadata(&file)?.permissions();
+    permissions.set_readonly(true);
+    std::fs::set_permissions(&file, permissions)?;
+
+    let entry = ls_detailed(dir.path())?.collect_results()?.remove(0);
+    assert!(entry.accessed().is_some());
+    assert!(entry.readonly());
+    #[cfg(unix)]
+    assert_eq!(entry.mode() & 0o222, 0);
+    Ok(())
+}
+
+#[test]
+fn path_entries_sort_by_path() -> crate::Result<()> {
+    let dir = tempdir()?;
+    for name in ["c.txt", "a.txt", "b.txt"] {
+        write_text(dir.path().join(name), name)?;
+    }
+    let mut entries = ls_detailed(dir.path())?.collect_results()?;
+    entries.sort();
+    let names: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.file_name().unwrap().to_owned())
+        .collect();
+    assert_eq!(names, vec!["a.txt", "b.txt", "c.txt"]);
+
+    let sorted: Vec<_> = walk_detailed(dir.path())?
+        .filter_map(|entry| entry.ok())
+        .sorted()
+        .collect();
+    assert!(sorted.windows(2).all(|pair| pair[0].path < pair[1].path));
+    Ok(())
+}
@@ -1,10 +1,14 @@
 use crate::{Error, Result, Shell};
 
 use std::{
-    fs,
+    collections::VecDeque,
+    fs, io,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
+use glob::Pattern;
+
 use super::entries::PathEntry;
 
 /// Lists the immediate children of a directory.
@@ -23,6 +27,27 @@ pub fn ls_detailed(path: impl AsRef<Path>) -> Result<Shell<Result<PathEntry>>> {
 pub fn walk(root: impl AsRef<Path>) -> Result<Shell<Result<PathBuf>>> {
     Ok(Shell::new(Box::new(WalkIter::new(
         root.as_ref().to_path_buf(),
+        None,
+    ))))
+}
+
+/// Like [`walk`], but does not descend more than `max_depth` levels below the root.
+///
+/// A depth of 0 yields only the root, 1 adds its immediate children, and so on.
+pub fn walk_depth(root: impl AsRef<Path>, max_depth: usize) -> Result<Shell<Result<PathBuf>>> {
+    Ok(Shell::new(Box::new(WalkIter::new(
+        root.as_ref().to_path_buf(),
+        Some(max_depth),
+    ))))
+}
+
+/// Recursively walks the directory tree breadth-first including the root.
+///
+/// Yields the root, then every entry at depth 1, then depth 2, and so on.
+/// Like [`walk`], symlinked directories are not descended into.
+pub fn walk_bfs(root: impl AsRef<Path>) -> Result<Shell<Result<PathBuf>>> {
+    Ok(Shell::new(Box::new(BfsWalkIter::new(
+        root.as_ref().to_path_buf(),
     ))))
 }
 
@@ -30,6 +55,18 @@ pub fn walk(root: impl AsRef<Path>) -> Result<Shell<Result<PathBuf>>> {
 pub fn walk_detailed(root: impl AsRef<Path>) -> Result<Shell<Result<PathEntry>>> {
     Ok(Shell::new(Box::new(WalkDetailedIter::new(
         root.as_ref().to_path_buf(),
+        None,
+    ))))
+}
+
+/// Like [`walk_detailed`], but does not descend more than `max_depth` levels below the root.
+pub fn walk_detailed_depth(
+    root: impl AsRef<Path>,
+    max_depth: usize,
+) -> Result<Shell<Result<PathEntry>>> {
+    Ok(Shell::new(Box::new(WalkDetailedIter::new(
+        root.as_ref().to_path_buf(),
+        Some(max_depth),
     ))))
 }
 
@@ -53,6 +90,90 @@ where
     }))
 }
 
+/// Walks the tree, then evaluates `predicate` across rayon threads.
+///
+/// Unlike [`walk_filter`] this is eager: the whole tree is collected before any
+/// predicate runs, and the first traversal error aborts the call. Matching
+/// entries keep their walk order. Requires `--features parallel`.
+#[cfg(feature = "parallel")]
+pub fn walk_filter_parallel<F>(root: impl AsRef<Path>, predicate: F) -> Result<Vec<PathEntry>>
+where
+    F: Fn(&PathEntry) -> bool + Send + Sync,
+{
+    use rayon::prelude::*;
+    let entries = walk_detailed(root)?.collect_results()?;
+    Ok(entries
+        .into_par_iter()
+        .filter(|entry| predicate(entry))
+        .collect())
+}
+
+/// Builder for recursive walks that prune directories before descending.
+///
+/// Unlike [`walk_filter`], excluded directories are never read, so nothing
+/// beneath them is visited.
+#[derive(Debug, Clone)]
+pub struct WalkBuilder {
+    root: PathBuf,
+    excludes: Vec<String>,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+}
+
+impl WalkBuilder {
+    /// Starts configuring a walk rooted at `root`.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            excludes: Vec::new(),
+            max_depth: None,
+            follow_symlinks: false,
+        }
+    }
+
+    /// Skips entries matching the glob `pattern`, without descending into them.
+    ///
+    /// Patterns are matched against the path relative to the root and against
+    /// the bare file name, so both `target` and `crates/*/target` work.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.excludes.push(pattern.into());
+        self
+    }
+
+    /// Limits recursion: `0` yields only the root, `1` adds its children, and so on.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Descends into symlinked directories.
+    ///
+    /// A link is skipped only when it points back at one of its own ancestors,
+    /// so cycles terminate while a linked directory and its target are both
+    /// walked.
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Starts the walk, failing if an exclude pattern is invalid.
+    pub fn build(self) -> Result<Shell<Result<PathEntry>>> {
+        let excludes = self
+            .excludes
+            .iter()
+            .map(|pattern| Pattern::new(pattern))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Shell::new(ConfiguredWalkIter {
+            stack: vec![(self.root.clone(), 0, Rc::new(Vec::new()))],
+            root: self.root,
+            excludes,
+            max_depth: self.max_depth,
+            follow_symlinks: self.follow_symlinks,
+            pending_err: None,
+        }))
+    }
+}
+
 fn is_file_or_symlink_to_file(entry: &PathEntry) -> bool {
     if entry.is_file() {
         return true;
@@ -110,34 +231,43 @@ impl Iterator for ReadDirDetailed {
     }
 }
 
+fn walk_error(path: &Path, source: io::Error) -> Error {
+    Error::Walk {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
 struct WalkIter {
-    stack: Vec<PathBuf>,
+    stack: Vec<(PathBuf, usize)>,
+    max_depth: Option<usize>,
     pending_err: Option<Error>,
 }
 
 impl WalkIter {
-    fn new(root: PathBuf) -> Self {
+    fn new(root: PathBuf, max_depth: Option<usize>) -> Self {
         Self {
-            stack: vec![root],
+            stack: vec![(root, 0)],
+            max_depth,
             pending_err: None,
         }
     }
 
-    fn push_children(&mut self, dir: &Path) {
+    fn push_children(&mut self, dir: &Path, depth: usize) {
         match fs::read_dir(dir) {
             Ok(read_dir) => {
                 for entry in read_dir {
                     match entry {
-                        Ok(entry) => self.stack.push(entry.path()),
+                        Ok(entry) => self.stack.push((entry.path(), depth)),
                         Err(err) => {
-                            self.pending_err = Some(err.into());
+                            self.pending_err = Some(walk_error(dir, err));
                             break;
                         }
                     }
                 }
             }
             Err(err) => {
-                self.pending_err = Some(err.into());
+                self.pending_err = Some(walk_error(dir, err));
             }
         }
     }
@@ -150,30 +280,87 @@ impl Iterator for WalkIter {
         if let Some(err) = self.pending_err.take() {
             return Some(Err(err));
         }
-        let path = self.stack.pop()?;
+        let (path, depth) = self.stack.pop()?;
         let should_descend = match fs::symlink_metadata(&path) {
             Ok(meta) => meta.file_type().is_dir() && !meta.file_type().is_symlink(),
             Err(err) => {
-                self.pending_err = Some(err.into());
+                self.pending_err = Some(walk_error(&path, err));
+                false
+            }
+        };
+        if should_descend && self.max_depth.is_none_or(|max| depth < max) {
+            self.push_children(&path, depth + 1);
+        }
+        Some(Ok(path))
+    }
+}
+
+struct BfsWalkIter {
+    queue: VecDeque<PathBuf>,
+    pending_err: Option<Error>,
+}
+
+impl BfsWalkIter {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            queue: VecDeque::from([root]),
+            pending_err: None,
+        }
+    }
+
+    fn enqueue_children(&mut self, dir: &Path) {
+        match fs::read_dir(dir) {
+            Ok(read_dir) => {
+                for entry in read_dir {
+                    match entry {
+                        Ok(entry) => self.queue.push_back(entry.path()),
+                        Err(err) => {
+                            self.pending_err = Some(walk_error(dir, err));
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                self.pending_err = Some(walk_error(dir, err));
+            }
+        }
+    }
+}
+
+impl Iterator for BfsWalkIter {
+    type Item = Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.pending_err.take() {
+            return Some(Err(err));
+        }
+        let path = self.queue.pop_front()?;
+        let should_descend = match fs::symlink_metadata(&path) {
+            Ok(meta) => meta.file_type().is_dir() && !meta.file_type().is_symlink(),
+            Err(err) => {
+                self.pending_err = Some(walk_error(&path, err));
                 false
             }
         };
         if should_descend {
-            self.push_children(&path);
+            self.enqueue_children(&path);
         }
         Some(Ok(path))
     }
 }
 
 struct WalkDetailedIter {
-    stack: Vec<PathBuf>,
+    stack: Vec<(PathBuf, usize)>,
+    max_depth: Option<usize>,
     pending_err: Option<Error>,
 }
 
 impl WalkDetailedIter {
-    fn new(root: PathBuf) -> Self {
+    fn new(root: PathBuf, max_depth: Option<usize>) -> Self {
         Self {
-            stack: vec![root],
+            stack: vec![(root, 0)],
+            max_depth,
             pending_err: None,
         }
     }
@@ -186,35 +373,131 @@ impl Iterator for WalkDetailedIter {
         if let Some(err) = self.pending_err.take() {
             return Some(Err(err));
         }
-        let path = self.stack.pop()?;
+        let (path, depth) = self.stack.pop()?;
         let metadata = match fs::symlink_metadata(&path) {
             Ok(metadata) => metadata,
-            Err(err) => return Some(Err(err.into())),
+            Err(err) => return Some(Err(walk_error(&path, err))),
         };
         let should_descend = match fs::symlink_metadata(&path) {
             Ok(meta) => meta.file_type().is_dir() && !meta.file_type().is_symlink(),
             Err(err) => {
-                self.pending_err = Some(err.into());
+                self.pending_err = Some(walk_error(&path, err));
                 false
             }
         };
-        if should_descend {
+        if should_descend && self.max_depth.is_none_or(|max| depth < max) {
             match fs::read_dir(&path) {
                 Ok(read_dir) => {
                     for entry in read_dir {
                         match entry {
-                            Ok(entry) => self.stack.push(entry.path()),
+                            Ok(entry) => self.stack.push((entry.path(), depth + 1)),
                             Err(err) => {
-                                self.pending_err = Some(err.into());
+                                self.pending_err = Some(walk_error(&path, err));
                                 break;
                             }
                         }
                     }
                 }
                 Err(err) => {
-                    self.pending_err = Some(err.into());
+                    self.pending_err = Some(walk_error(&path, err));
+                }
+            }
+        }
+        Some(Ok(PathEntry { path, metadata }))
+    }
+}
+
+/// Canonical paths of the directories enclosing a stack entry, used to spot
+/// symlink cycles when following links.
+type Ancestors = Rc<Vec<PathBuf>>;
+
+struct ConfiguredWalkIter {
+    root: PathBuf,
+    stack: Vec<(PathBuf, usize, Ancestors)>,
+    excludes: Vec<Pattern>,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    pending_err: Option<Error>,
+}
+
+impl ConfiguredWalkIter {
+    fn is_excluded(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let name = path.file_name().map(|name| name.to_string_lossy());
+        self.excludes.iter().any(|pattern| {
+            pattern.matches_path(relative)
+                || name.as_ref().is_some_and(|name| pattern.matches(name))
+        })
+    }
+
+    /// Returns the ancestor chain for `dir`'s children, or `None` when `dir`
+    /// must not be entered because it loops back to an ancestor.
+    fn enter(&mut self, dir: &Path, ancestors: Ancestors) -> Option<Ancestors> {
+        if !self.follow_symlinks {
+            return Some(ancestors);
+        }
+        match fs::canonicalize(dir) {
+            Ok(canonical) if ancestors.contains(&canonical) => None,
+            Ok(canonical) => {
+                let mut chain = ancestors.as_ref().clone();
+                chain.push(canonical);
+                Some(Rc::new(chain))
+            }
+            Err(err) => {
+                self.pending_err = Some(walk_error(dir, err));
+                None
+            }
+        }
+    }
+
+    fn push_children(&mut self, dir: &Path, depth: usize, ancestors: Ancestors) {
+        match fs::read_dir(dir) {
+            Ok(read_dir) => {
+                for entry in read_dir {
+                    match entry {
+                        Ok(entry) => {
+                            let path = entry.path();
+                            if !self.is_excluded(&path) {
+                                self.stack.push((path, depth, Rc::clone(&ancestors)));
+                            }
+                        }
+                        Err(err) => {
+                            self.pending_err = Some(walk_error(dir, err));
+                            break;
+                        }
+                    }
                 }
             }
+            Err(err) => {
+                self.pending_err = Some(walk_error(dir, err));
+            }
+        }
+    }
+}
+
+impl Iterator for ConfiguredWalkIter {
+    type Item = Result<PathEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.pending_err.take() {
+            return Some(Err(err));
+        }
+        let (path, depth, ancestors) = self.stack.pop()?;
+        let metadata = if self.follow_symlinks {
+            fs::metadata(&path).or_else(|_| fs::symlink_metadata(&path))
+        } else {
+            fs::symlink_metadata(&path)
+        };
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(err) => return Some(Err(walk_error(&path, err))),
+        };
+        let within_depth = self.max_depth.is_none_or(|max| depth < max);
+        if metadata.is_dir()
+            && within_depth
+            && let Some(ancestors) = self.enter(&path, ancestors)
+        {
+            self.push_children(&path, depth + 1, ancestors);
         }
         Some(Ok(PathEntry { path, metadata }))
     }
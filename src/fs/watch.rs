@@ -1,13 +1,13 @@
 use crate::{Result, Shell};
 
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
     path::{Path, PathBuf},
     thread,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
-use std::sync::mpsc::{self, Receiver};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
 
 #[cfg(feature = "async")]
 use tokio::{sync::mpsc as async_mpsc, task};
@@ -225,6 +225,91 @@ pub fn debounce_watch(
     })
 }
 
+/// Watches `root`, collapsing bursts of events for the same path.
+///
+/// Events are gathered for `window` after the first one arrives. Within that
+/// batch, repeated `Created`/`Modified` events for a path merge into one slot
+/// carrying the latest metadata (a `Created` stays `Created`). `Removed` and
+/// `Renamed` are always kept and end the slot for their paths, so a later write
+/// to the same path is reported separately. Batches preserve arrival order.
+pub fn watch_coalesced(
+    root: impl AsRef<Path>,
+    window: Duration,
+) -> Result<Shell<Result<WatchEvent>>> {
+    let rx = Watcher::new(root)?.into_receiver();
+    Ok(Shell::new(CoalescingIter {
+        rx,
+        window,
+        pending: VecDeque::new(),
+    }))
+}
+
+struct CoalescingIter {
+    rx: Receiver<Result<WatchEvent>>,
+    window: Duration,
+    pending: VecDeque<Result<WatchEvent>>,
+}
+
+impl CoalescingIter {
+    fn collect_batch(&mut self, first: Result<WatchEvent>) {
+        let deadline = Instant::now() + self.window;
+        let mut batch = Vec::new();
+        let mut slots = HashMap::new();
+        let mut event = first;
+        loop {
+            match event {
+                Ok(WatchEvent::Created(entry) | WatchEvent::Modified(entry))
+                    if slots.contains_key(&entry.path) =>
+                {
+                    if let Some(Ok(
+                        WatchEvent::Created(existing) | WatchEvent::Modified(existing),
+                    )) = batch.get_mut(slots[&entry.path])
+                    {
+                        *existing = entry;
+                    }
+                }
+                Ok(WatchEvent::Created(entry)) => {
+                    slots.insert(entry.path.clone(), batch.len());
+                    batch.push(Ok(WatchEvent::Created(entry)));
+                }
+                Ok(WatchEvent::Modified(entry)) => {
+                    slots.insert(entry.path.clone(), batch.len());
+                    batch.push(Ok(WatchEvent::Modified(entry)));
+                }
+                Ok(WatchEvent::Removed { path, was_dir }) => {
+                    slots.remove(&path);
+                    batch.push(Ok(WatchEvent::Removed { path, was_dir }));
+                }
+                Ok(WatchEvent::Renamed { from, to, entry }) => {
+                    slots.remove(&from);
+                    slots.remove(&to);
+                    batch.push(Ok(WatchEvent::Renamed { from, to, entry }));
+                }
+                Err(err) => batch.push(Err(err)),
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.rx.recv_timeout(remaining) {
+                Ok(next) => event = next,
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        self.pending.extend(batch);
+    }
+}
+
+impl Iterator for CoalescingIter {
+    type Item = Result<WatchEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+        let first = self.rx.recv().ok()?;
+        self.collect_batch(first);
+        self.pending.pop_front()
+    }
+}
+
 /// Convenience helper composing `watch`, `debounce_watch`, and `watch_glob`.
 pub fn watch_filtered(
     root: impl AsRef<Path>,
@@ -236,6 +321,23 @@ pub fn watch_filtered(
     watch_glob(debounced, pattern)
 }
 
+/// Like [`watch_filtered`], but yields the changed [`PathEntry`] instead of the event.
+///
+/// Removals (and renames whose target vanished) carry no entry and are skipped.
+pub fn watch_paths(
+    root: impl AsRef<Path>,
+    debounce_window: Duration,
+    pattern: impl AsRef<str>,
+) -> Result<Shell<Result<PathEntry>>> {
+    let events = watch_filtered(root, debounce_window, pattern)?;
+    Ok(events.filter_map(|event| match event {
+        Ok(WatchEvent::Created(entry) | WatchEvent::Modified(entry)) => Some(Ok(entry)),
+        Ok(WatchEvent::Renamed { entry, .. }) => entry.map(Ok),
+        Ok(WatchEvent::Removed { .. }) => None,
+        Err(err) => Some(Err(err)),
+    }))
+}
+
 /// Async watch helper that polls using `tokio::task::spawn_blocking`.
 #[cfg(feature = "async")]
 pub async fn watch_async(
@@ -11,23 +11,42 @@ mod error;
 mod fs;
 #[doc(hidden)]
 pub mod macros;
+mod quote;
 mod shell;
 
 pub mod prelude;
 
-pub use command::{Command, CommandOutput, Pipeline, cmd, sh};
+#[cfg(unix)]
+pub use command::sh_args;
+pub use command::{
+    Command, CommandMetrics, CommandOutput, Pipeline, bridge, cmd, from_argv, run_all, sh, sh_in,
+};
 pub use env::*;
 pub use error::{Error, Result};
 pub use fs::{
-    PathEntry, WatchEvent, Watcher, append_text, cat, copy_dir, copy_entries, copy_file,
-    debounce_watch, filter_extension, filter_modified_since, filter_size, glob, glob_entries, ls,
-    ls_detailed, mkdir_all, move_path, read_lines, read_text, rm, temp_file, walk, walk_detailed,
-    walk_files, walk_filter, watch, watch_filtered, watch_glob, write_lines, write_text,
+    CopyDirOptions, CopyOptions, CopyProgress, GlobOptions, PathEntry, ReadOptions, WalkBuilder,
+    WatchEvent, Watcher, append_lines_from, append_text, cat, cat_glob, copy_dir,
+    copy_dir_incremental, copy_dir_with, copy_entries, copy_file, copy_file_if_newer,
+    copy_file_with, debounce_watch, filter_contains, filter_extension, filter_glob,
+    filter_modified_before, filter_modified_between, filter_modified_since, filter_size,
+    filter_size_max, filter_size_range, glob, glob_count, glob_entries, glob_entries_with,
+    glob_with, join_files, ls, ls_detailed, mkdir_all, move_path, read_lines, read_lines_range,
+    read_lines_rev, read_lines_with, read_text, rm, split_file, tail, tail_bytes, temp_dir,
+    temp_file, walk, walk_bfs, walk_depth, walk_detailed, walk_detailed_depth, walk_files,
+    walk_filter, watch, watch_coalesced, watch_filtered, watch_glob, watch_paths, with_temp_file,
+    write_lines, write_lines_from, write_text, write_text_atomic,
 };
+pub use quote::quote;
 
+#[cfg(feature = "regex")]
+pub use fs::filter_matches;
+#[cfg(feature = "trash")]
+pub use fs::rm_to_trash;
+#[cfg(feature = "parallel")]
+pub use fs::walk_filter_parallel;
 #[cfg(feature = "async")]
 pub use fs::{watch_async, watch_async_stream, watch_filtered_async};
-pub use shell::{DoubleEndedShell, Shell};
+pub use shell::{DoubleEndedShell, PeekableShell, Shell, ShellBuilder};
 
 /// Convenience module with the most frequently used items.
 ///
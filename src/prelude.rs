@@ -1,16 +1,36 @@
 pub use crate::{
-    DoubleEndedShell, Shell, cmd,
-    command::{Command, CommandOutput, Pipeline, sh},
+    DoubleEndedShell, PeekableShell, Shell, ShellBuilder, cmd,
+    command::{
+        Command, CommandMetrics, CommandOutput, Pipeline, bridge, from_argv, run_all, sh, sh_in,
+    },
     fs::{
-        GlobCache, PathEntry, WatchEvent, Watcher, append_text, cat, copy_dir, copy_entries,
-        copy_file, debounce_watch, filter_extension, filter_modified_since, filter_size, glob,
-        glob_entries, ls, ls_detailed, mkdir_all, move_path, read_lines, read_text, rm, temp_file,
-        walk, walk_detailed, walk_files, walk_filter, watch, watch_channel, watch_filtered,
-        watch_glob, write_lines, write_text,
+        CopyDirOptions, CopyOptions, CopyProgress, GlobCache, GlobOptions, PathEntry, ReadOptions,
+        WalkBuilder, WatchEvent, Watcher, append_lines_from, append_text, cat, cat_glob, copy_dir,
+        copy_dir_incremental, copy_dir_with, copy_entries, copy_file, copy_file_if_newer,
+        copy_file_with, debounce_watch, filter_contains, filter_extension, filter_glob,
+        filter_modified_before, filter_modified_between, filter_modified_since, filter_size,
+        filter_size_max, filter_size_range, glob, glob_count, glob_entries, glob_entries_with,
+        glob_with, join_files, ls, ls_detailed, mkdir_all, move_path, read_lines, read_lines_range,
+        read_lines_rev, read_lines_with, read_text, rm, split_file, tail, tail_bytes, temp_dir,
+        temp_file, walk, walk_bfs, walk_depth, walk_detailed, walk_detailed_depth, walk_files,
+        walk_filter, watch, watch_channel, watch_coalesced, watch_filtered, watch_glob,
+        watch_paths, with_temp_file, write_lines, write_lines_from, write_text, write_text_atomic,
     },
-    home_dir, path_entries, remove_var, set_var, var, which,
+    home_dir, path_entries, quote, remove_var, set_var, var, which, which_ext,
 };
 
+#[cfg(unix)]
+pub use crate::command::sh_args;
+
+#[cfg(feature = "parallel")]
+pub use crate::fs::walk_filter_parallel;
+
+#[cfg(feature = "trash")]
+pub use crate::fs::rm_to_trash;
+
+#[cfg(feature = "regex")]
+pub use crate::fs::filter_matches;
+
 #[cfg(feature = "async")]
 pub use crate::fs::{watch_async, watch_async_stream, watch_filtered_async};
 
@@ -0,0 +1,115 @@
+//! Quoting helpers for building shell snippets from untrusted data.
+
+/// Quotes `word` so the platform shell (`sh`, or `cmd.exe` on Windows) reads it
+/// back as a single literal argument.
+///
+/// Plain words made of safe characters are returned unchanged. On Unix anything
+/// else is wrapped in single quotes; on Windows the word is double-quoted for the
+/// C runtime argument parser and cmd.exe metacharacters are caret-escaped.
+///
+/// ```
+/// # #[cfg(unix)]
+/// # fn main() -> qshr::Result<()> {
+/// use qshr::{quote, sh};
+///
+/// let user_input = "it's $HOME; rm -rf /";
+/// let echoed = sh(format!("printf %s {}", quote(user_input))).stdout_text()?;
+/// assert_eq!(echoed, user_input);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(unix))]
+/// # fn main() {}
+/// ```
+pub fn quote(word: &str) -> String {
+    if cfg!(windows) {
+        quote_windows(word)
+    } else {
+        quote_posix(word)
+    }
+}
+
+fn quote_posix(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./=:,+@%".contains(c));
+    if plain {
+        return word.to_string();
+    }
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+fn quote_windows(word: &str) -> String {
+    let plain = !word.is_empty()
+        && !word
+            .chars()
+            .any(|c| c.is_whitespace() || "\"&|<>^%!()".contains(c));
+    if plain {
+        return word.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in word.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        if c == '"' {
+            quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+        } else {
+            quoted.push_str(&"\\".repeat(backslashes));
+        }
+        quoted.push(c);
+        backslashes = 0;
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    let mut escaped = String::with_capacity(quoted.len());
+    for c in quoted.chars() {
+        if "\"&|<>^%!()".contains(c) {
+            escaped.push('^');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quote, quote_posix, quote_windows};
+
+    #[test]
+    fn posix_quoting() {
+        assert_eq!(quote_posix("plain-word.txt"), "plain-word.txt");
+        assert_eq!(quote_posix(""), "''");
+        assert_eq!(quote_posix("two words"), "'two words'");
+        assert_eq!(quote_posix("it's"), "'it'\\''s'");
+        assert_eq!(quote_posix("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn windows_quoting() {
+        assert_eq!(quote_windows("plain"), "plain");
+        assert_eq!(quote_windows("two words"), "^\"two words^\"");
+        assert_eq!(quote_windows("say \"hi\""), "^\"say \\^\"hi\\^\"^\"");
+        assert_eq!(quote_windows("C:\\dir\\ x\\"), "^\"C:\\dir\\ x\\\\^\"");
+        assert_eq!(quote_windows("a&b"), "^\"a^&b^\"");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn quoted_words_roundtrip_through_sh() -> crate::Result<()> {
+        for word in [
+            "plain",
+            "two words",
+            "it's a 'quote'",
+            "line one\nline two",
+            "$HOME and ${PATH} and $(id)",
+            "",
+        ] {
+            let echoed = crate::sh(format!("printf %s {}", quote(word))).stdout_text()?;
+            assert_eq!(echoed, word);
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 mod base;
 mod iters;
 
-pub use base::{DoubleEndedShell, Shell};
+pub use base::{DoubleEndedShell, PeekableShell, Shell, ShellBuilder};
 
 #[cfg(test)]
 mod tests;
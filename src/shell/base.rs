@@ -1,8 +1,16 @@
-use std::iter;
+use crate::{Error, Result};
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::io::BufRead;
+use std::iter::{self, Peekable};
+use std::rc::Rc;
 use std::sync::Arc;
 
 use super::iters::{
-    ChunkIter, ChunkMapIter, DistinctIter, InterleaveIter, ProductIter, WindowIter,
+    ChunkIter, ChunkMapIter, DedupByKeyIter, DedupRecentIter, DistinctIter, InterleaveIter,
+    IntersperseWithIter, ProductIter, ScanFramesIter, WindowIter, WithoutLastIter,
 };
 
 /// A lazy, composable stream of values inspired by Turtle's `Shell`.
@@ -18,6 +26,23 @@ pub struct DoubleEndedShell<T> {
     iter: Box<dyn DoubleEndedIterator<Item = T> + 'static>,
 }
 
+/// A [`Shell`] that can look one element ahead.
+///
+/// Created by [`Shell::peekable`]. Peeking pulls the next item from the
+/// underlying boxed iterator and buffers it until `next` is called.
+pub struct PeekableShell<T> {
+    iter: Peekable<Box<dyn Iterator<Item = T> + 'static>>,
+}
+
+/// Collects items pushed one at a time, then hands them out as a [`Shell`].
+///
+/// Useful when items are assembled in a loop with early exits, where an
+/// iterator chain would be awkward.
+#[derive(Debug, Clone)]
+pub struct ShellBuilder<T> {
+    items: Vec<T>,
+}
+
 impl<T> Shell<T> {
     /// Wraps an arbitrary iterator.
     pub fn new<I>(iter: I) -> Self
@@ -99,6 +124,28 @@ impl<T> Shell<T> {
         Shell::new(iter.flat_map(f))
     }
 
+    /// Expands every item into a sub-[`Shell`], yielding the sub-streams lazily in order.
+    pub fn flat_map_shell<U, F>(self, f: F) -> Shell<U>
+    where
+        U: 'static,
+        F: FnMut(T) -> Shell<U> + 'static,
+        T: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(iter.flat_map(f))
+    }
+
+    /// Flattens a stream of iterables, like [`Shell::then`] without a closure.
+    pub fn flatten<U>(self) -> Shell<U>
+    where
+        T: IntoIterator<Item = U> + 'static,
+        T::IntoIter: 'static,
+        U: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(iter.flatten())
+    }
+
     /// Yields at most `n` elements.
     pub fn take(self, n: usize) -> Shell<T>
     where
@@ -148,6 +195,51 @@ impl<T> Shell<T> {
         Shell::new(iter.chain(other))
     }
 
+    /// Yields `item` before the rest of the stream.
+    pub fn prepend(self, item: T) -> Shell<T>
+    where
+        T: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(iter::once(item).chain(iter))
+    }
+
+    /// Yields `item` after the rest of the stream.
+    pub fn append(self, item: T) -> Shell<T>
+    where
+        T: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(iter.chain(iter::once(item)))
+    }
+
+    /// Drops the final element, holding back at most one item at a time.
+    ///
+    /// This is the common single-element case of trimming a trailing suffix;
+    /// an empty stream stays empty.
+    pub fn without_last(self) -> Shell<T>
+    where
+        T: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(WithoutLastIter::new(iter))
+    }
+
+    /// Repeats the stream until `total` items have been yielded.
+    ///
+    /// The whole source is buffered eagerly before the first item is produced.
+    /// An empty source yields nothing rather than looping forever.
+    pub fn cycle_take(self, total: usize) -> Shell<T>
+    where
+        T: Clone + 'static,
+    {
+        let items = self.to_vec();
+        if items.is_empty() {
+            return Shell::empty();
+        }
+        Shell::new(items.into_iter().cycle().take(total))
+    }
+
     /// Enumerates elements, pairing them with their index.
     pub fn enumerate(self) -> Shell<(usize, T)>
     where
@@ -157,6 +249,18 @@ impl<T> Shell<T> {
         Shell::new(iter.enumerate())
     }
 
+    /// Threads mutable state through the stream, stopping once `f` returns `None`.
+    pub fn scan<St, U, F>(self, initial: St, f: F) -> Shell<U>
+    where
+        St: 'static,
+        F: FnMut(&mut St, T) -> Option<U> + 'static,
+        T: 'static,
+        U: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(iter.scan(initial, f))
+    }
+
     /// Runs the provided closure for each item while keeping the item in the stream.
     pub fn inspect<F>(self, f: F) -> Shell<T>
     where
@@ -167,9 +271,96 @@ impl<T> Shell<T> {
         Shell::new(iter.inspect(f))
     }
 
-    /// Collects the stream into a `Vec`.
+    /// Buffers the whole stream so it can be consumed from both ends.
+    ///
+    /// Useful for sources such as [`read_lines`](crate::read_lines) that can
+    /// only be read front-to-back.
+    pub fn into_double_ended(self) -> DoubleEndedShell<T>
+    where
+        T: 'static,
+    {
+        DoubleEndedShell::from_vec(self.to_vec())
+    }
+
+    /// Wraps the stream so the next element can be inspected without consuming it.
+    pub fn peekable(self) -> PeekableShell<T>
+    where
+        T: 'static,
+    {
+        PeekableShell {
+            iter: self.into_boxed().peekable(),
+        }
+    }
+
+    /// Collects the stream into a `Vec`. Alias for [`Shell::collect_vec`].
     pub fn to_vec(self) -> Vec<T> {
-        self.into_iter().collect()
+        self.collect_vec()
+    }
+
+    /// Collects the stream into a `Vec`, pre-allocating from the size hint's lower bound.
+    pub fn collect_vec(self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len_hint().0);
+        vec.extend(self.into_boxed());
+        vec
+    }
+
+    /// Consumes the stream, returning the number of elements.
+    pub fn count(self) -> usize {
+        self.into_boxed().count()
+    }
+
+    /// Consumes the stream, returning the final element.
+    pub fn last(self) -> Option<T> {
+        self.into_boxed().last()
+    }
+
+    /// Returns the element at index `n`, consuming everything up to it.
+    ///
+    /// The rest of the stream stays available, as with [`Iterator::nth`].
+    pub fn nth(&mut self, n: usize) -> Option<T> {
+        self.iter.nth(n)
+    }
+
+    /// Sums every element.
+    pub fn sum<S>(self) -> S
+    where
+        S: iter::Sum<T>,
+    {
+        self.into_boxed().sum()
+    }
+
+    /// Returns the largest element, or the last of several equal maxima.
+    pub fn max(self) -> Option<T>
+    where
+        T: Ord,
+    {
+        self.into_boxed().max()
+    }
+
+    /// Returns the smallest element, or the first of several equal minima.
+    pub fn min(self) -> Option<T>
+    where
+        T: Ord,
+    {
+        self.into_boxed().min()
+    }
+
+    /// Returns the arithmetic mean, or `None` for an empty stream.
+    pub fn average(self) -> Option<f64>
+    where
+        T: Into<f64>,
+    {
+        self.mean_and_variance().map(|(mean, _)| mean)
+    }
+
+    /// Returns the population variance, or `None` for an empty stream.
+    ///
+    /// Computed in a single pass with Welford's algorithm.
+    pub fn variance(self) -> Option<f64>
+    where
+        T: Into<f64>,
+    {
+        self.mean_and_variance().map(|(_, variance)| variance)
     }
 
     /// Returns the iterator size hint.
@@ -185,6 +376,33 @@ impl<T> Shell<T> {
         self.into_iter().collect()
     }
 
+    /// Groups every element by the key returned from `key_fn`.
+    ///
+    /// Unlike adjacent grouping this buffers the whole stream, so equal keys are
+    /// merged no matter where they appear. Values keep their stream order.
+    pub fn group_into_map<K, F>(self, mut key_fn: F) -> HashMap<K, Vec<T>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&T) -> K,
+    {
+        let mut groups: HashMap<K, Vec<T>> = HashMap::new();
+        for item in self {
+            groups.entry(key_fn(&item)).or_default().push(item);
+        }
+        groups
+    }
+
+    /// Buckets every element by `key`; an alias for [`Shell::group_into_map`].
+    ///
+    /// This is an eager, terminal operation. Each bucket keeps stream order.
+    pub fn group_by<K, F>(self, key: F) -> HashMap<K, Vec<T>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&T) -> K,
+    {
+        self.group_into_map(key)
+    }
+
     /// Groups elements into non-overlapping chunks.
     pub fn chunks(self, size: usize) -> Shell<Vec<T>>
     where
@@ -195,6 +413,16 @@ impl<T> Shell<T> {
         Shell::new(ChunkIter::new(iter, size))
     }
 
+    /// Like [`Shell::chunks`], but returns an error instead of panicking when
+    /// `size` is zero.
+    pub fn try_chunks(self, size: usize) -> Result<Shell<Vec<T>>>
+    where
+        T: 'static,
+    {
+        ensure_nonzero(size, "chunk size")?;
+        Ok(self.chunks(size))
+    }
+
     /// Produces sliding windows of size `size`. Requires `T: Clone`.
     pub fn windows(self, size: usize) -> Shell<Vec<T>>
     where
@@ -205,6 +433,54 @@ impl<T> Shell<T> {
         Shell::new(WindowIter::new(iter, size))
     }
 
+    /// Like [`Shell::windows`], but returns an error instead of panicking when
+    /// `size` is zero.
+    pub fn try_windows(self, size: usize) -> Result<Shell<Vec<T>>>
+    where
+        T: Clone + 'static,
+    {
+        ensure_nonzero(size, "window size")?;
+        Ok(self.windows(size))
+    }
+
+    /// Like [`Shell::windows`], but shares items between overlapping windows.
+    ///
+    /// Each item is wrapped in an [`Rc`] once, so building a window costs
+    /// refcount bumps rather than deep clones and `T` need not be `Clone`.
+    /// The windows are not `Send`; collect them into owned values before
+    /// handing them to another thread.
+    pub fn windows_rc(self, size: usize) -> Shell<Vec<Rc<T>>>
+    where
+        T: 'static,
+    {
+        assert!(size > 0, "window size must be greater than zero");
+        let iter = self.into_boxed();
+        Shell::new(WindowIter::new(Box::new(iter.map(Rc::new)), size))
+    }
+
+    /// Like [`Shell::windows`], but yields a single window with every item when
+    /// the stream holds fewer than `size` elements. Empty streams yield nothing.
+    pub fn windows_or_all(self, size: usize) -> Shell<Vec<T>>
+    where
+        T: Clone + 'static,
+    {
+        assert!(size > 0, "window size must be greater than zero");
+        let iter = self.into_boxed();
+        Shell::new(WindowIter::with_partial(iter, size))
+    }
+
+    /// Yields each pair of adjacent items, like `windows(2)` as tuples.
+    pub fn pairwise(self) -> Shell<(T, T)>
+    where
+        T: Clone + 'static,
+    {
+        let mut previous: Option<T> = None;
+        let iter = self.into_boxed();
+        Shell::new(
+            iter.filter_map(move |item| previous.replace(item.clone()).map(|prev| (prev, item))),
+        )
+    }
+
     /// Interleaves this stream with another iterator.
     pub fn interleave<I>(self, other: I) -> Shell<T>
     where
@@ -217,6 +493,18 @@ impl<T> Shell<T> {
         Shell::new(InterleaveIter::new(iter_a, iter_b))
     }
 
+    /// Inserts a separator produced by `separator` between adjacent items.
+    ///
+    /// Separators are generated lazily, only when another item follows.
+    pub fn intersperse_with<F>(self, separator: F) -> Shell<T>
+    where
+        F: FnMut() -> T + 'static,
+        T: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(IntersperseWithIter::new(iter, separator))
+    }
+
     /// Computes the cartesian product of two streams.
     pub fn product<U, I>(self, other: I) -> Shell<(T, U)>
     where
@@ -272,6 +560,46 @@ impl<T> Shell<T> {
         acc
     }
 
+    /// Folds the stream, stopping at the first `Err` returned by `f`.
+    ///
+    /// Items after the failing one remain in the stream.
+    pub fn try_fold<U, E, F>(&mut self, init: U, f: F) -> std::result::Result<U, E>
+    where
+        F: FnMut(U, T) -> std::result::Result<U, E>,
+    {
+        self.iter.try_fold(init, f)
+    }
+
+    /// Folds the stream using the first element as the initial accumulator.
+    ///
+    /// Returns `None` for an empty stream.
+    pub fn reduce<F>(self, f: F) -> Option<T>
+    where
+        F: FnMut(T, T) -> T,
+    {
+        self.into_boxed().reduce(f)
+    }
+
+    /// Splits the stream into items matching `predicate` and the rest.
+    ///
+    /// Consumes the stream once, like [`Iterator::partition`].
+    pub fn partition<F, C>(self, mut predicate: F) -> (C, C)
+    where
+        C: Default + Extend<T>,
+        F: FnMut(&T) -> bool,
+    {
+        let mut matched = C::default();
+        let mut rejected = C::default();
+        for item in self {
+            if predicate(&item) {
+                matched.extend(iter::once(item));
+            } else {
+                rejected.extend(iter::once(item));
+            }
+        }
+        (matched, rejected)
+    }
+
     /// Applies a callback to every value, primarily for side effects.
     pub fn for_each(self, mut f: impl FnMut(T)) {
         for item in self {
@@ -279,6 +607,16 @@ impl<T> Shell<T> {
         }
     }
 
+    /// Runs a fallible callback for every value, stopping at the first `Err`.
+    ///
+    /// Items after the failing one remain in the stream.
+    pub fn try_for_each<E, F>(&mut self, f: F) -> std::result::Result<(), E>
+    where
+        F: FnMut(T) -> std::result::Result<(), E>,
+    {
+        self.iter.try_for_each(f)
+    }
+
     /// Returns only the first occurrence of each item.
     pub fn distinct(self) -> Shell<T>
     where
@@ -288,6 +626,39 @@ impl<T> Shell<T> {
         Shell::new(DistinctIter::new(iter))
     }
 
+    /// Drops consecutive duplicates, remembering only the previous element.
+    ///
+    /// Unlike [`Shell::distinct`], equal items separated by other values are kept.
+    pub fn dedup(self) -> Shell<T>
+    where
+        T: PartialEq + Clone + 'static,
+    {
+        self.dedup_by_key(T::clone)
+    }
+
+    /// Drops consecutive items whose keys compare equal.
+    pub fn dedup_by_key<K, F>(self, key_fn: F) -> Shell<T>
+    where
+        K: PartialEq + 'static,
+        F: FnMut(&T) -> K + 'static,
+        T: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(DedupByKeyIter::new(iter, key_fn))
+    }
+
+    /// Suppresses items already emitted within the last `window` outputs.
+    ///
+    /// Memory stays bounded by `window`, trading exactness for a sliding view;
+    /// a `window` of zero passes every item through.
+    pub fn dedup_recent(self, window: usize) -> Shell<T>
+    where
+        T: Eq + Hash + Clone + 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(DedupRecentIter::new(iter, window))
+    }
+
     /// Returns items sorted using their natural order.
     pub fn sorted(self) -> Shell<T>
     where
@@ -298,6 +669,29 @@ impl<T> Shell<T> {
         Shell::new(vec.into_iter())
     }
 
+    /// Returns items sorted with a custom comparator (stable).
+    pub fn sorted_by<F>(self, cmp: F) -> Shell<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        T: 'static,
+    {
+        let mut vec: Vec<T> = self.into_iter().collect();
+        vec.sort_by(cmp);
+        Shell::new(vec.into_iter())
+    }
+
+    /// Returns items sorted by the key extracted with `key` (stable).
+    pub fn sorted_by_key<K, F>(self, key: F) -> Shell<T>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+        T: 'static,
+    {
+        let mut vec: Vec<T> = self.into_iter().collect();
+        vec.sort_by_key(key);
+        Shell::new(vec.into_iter())
+    }
+
     /// Applies a function to chunks of items, yielding results once each chunk is processed.
     ///
     /// This placeholder implementation processes chunks sequentially but exposes
@@ -313,6 +707,18 @@ impl<T> Shell<T> {
         Shell::new(ChunkMapIter::new(iter, chunk_size, f))
     }
 
+    /// Like [`Shell::chunk_map`], but returns an error instead of panicking
+    /// when `chunk_size` is zero.
+    pub fn try_chunk_map<F, U>(self, chunk_size: usize, f: F) -> Result<Shell<U>>
+    where
+        F: FnMut(Vec<T>) -> Vec<U> + Send + 'static,
+        U: 'static + Send,
+        T: Send + 'static,
+    {
+        ensure_nonzero(chunk_size, "chunk size")?;
+        Ok(self.chunk_map(chunk_size, f))
+    }
+
     /// Applies a function to chunks in parallel when the `parallel` feature is enabled.
     ///
     /// Requires `--features parallel` (brings in the optional `rayon` dependency).
@@ -324,6 +730,40 @@ impl<T> Shell<T> {
         T: Send + 'static,
     {
         use rayon::prelude::*;
+        let chunks = self.collect_chunks(chunk_size);
+        let results: Vec<U> = chunks.into_par_iter().flat_map(f).collect();
+        Shell::new(results.into_iter())
+    }
+
+    /// Like [`Shell::chunk_map_parallel`], but runs on a dedicated pool of `threads` workers
+    /// instead of the global rayon pool.
+    ///
+    /// Output order matches the sequential [`Shell::chunk_map`]. Fails if the
+    /// thread pool cannot be built.
+    #[cfg(feature = "parallel")]
+    pub fn chunk_map_parallel_with<F, U>(
+        self,
+        chunk_size: usize,
+        threads: usize,
+        f: F,
+    ) -> Result<Shell<U>>
+    where
+        F: Fn(Vec<T>) -> Vec<U> + Send + Sync + 'static,
+        U: Send + 'static,
+        T: Send + 'static,
+    {
+        use rayon::prelude::*;
+        let chunks = self.collect_chunks(chunk_size);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(|err| Error::Io(std::io::Error::other(err)))?;
+        let results: Vec<U> = pool.install(|| chunks.into_par_iter().flat_map(f).collect());
+        Ok(Shell::new(results.into_iter()))
+    }
+
+    #[cfg(feature = "parallel")]
+    fn collect_chunks(self, chunk_size: usize) -> Vec<Vec<T>> {
         assert!(chunk_size > 0, "chunk size must be greater than zero");
         let mut chunks: Vec<Vec<T>> = Vec::new();
         let mut current = Vec::with_capacity(chunk_size);
@@ -336,15 +776,189 @@ impl<T> Shell<T> {
         if !current.is_empty() {
             chunks.push(current);
         }
-        let results: Vec<U> = chunks.into_par_iter().flat_map(f).collect();
+        chunks
+    }
+
+    /// Maps every element in parallel, preserving input order.
+    ///
+    /// Requires `--features parallel`. The stream is collected before mapping.
+    #[cfg(feature = "parallel")]
+    pub fn par_map<U, F>(self, f: F) -> Shell<U>
+    where
+        F: Fn(T) -> U + Send + Sync,
+        U: Send + 'static,
+        T: Send + 'static,
+    {
+        use rayon::prelude::*;
+        let items: Vec<T> = self.into_iter().collect();
+        let results: Vec<U> = items.into_par_iter().map(f).collect();
         Shell::new(results.into_iter())
     }
 
+    fn mean_and_variance(self) -> Option<(f64, f64)>
+    where
+        T: Into<f64>,
+    {
+        let mut count = 0u64;
+        let mut mean = 0.0;
+        let mut sum_sq = 0.0;
+        for value in self {
+            let value = value.into();
+            count += 1;
+            let delta = value - mean;
+            mean += delta / count as f64;
+            sum_sq += delta * (value - mean);
+        }
+        (count > 0).then(|| (mean, sum_sq / count as f64))
+    }
+
     fn into_boxed(self) -> Box<dyn Iterator<Item = T> + 'static> {
         self.iter
     }
 }
 
+impl<K, V> Shell<(K, V)> {
+    /// Collects key/value pairs into a sorted [`BTreeMap`]; later keys win.
+    pub fn collect_btreemap(self) -> BTreeMap<K, V>
+    where
+        K: Ord,
+    {
+        self.into_iter().collect()
+    }
+
+    /// Splits a stream of pairs into two collections, the inverse of [`Shell::zip`].
+    pub fn unzip<CK, CV>(self) -> (CK, CV)
+    where
+        CK: Default + Extend<K>,
+        CV: Default + Extend<V>,
+    {
+        self.into_boxed().unzip()
+    }
+}
+
+impl Shell<Vec<u8>> {
+    /// Accumulates byte chunks into a rolling buffer and extracts frames from it.
+    ///
+    /// After each chunk arrives, `f` is called with the buffer until it returns
+    /// `None`; `f` is expected to drain the bytes belonging to each frame it
+    /// returns. Bytes left over when the stream ends are discarded.
+    pub fn scan_frames<U, F>(self, f: F) -> Shell<U>
+    where
+        F: FnMut(&mut Vec<u8>) -> Option<U> + 'static,
+        U: 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(ScanFramesIter::new(iter, f))
+    }
+}
+
+impl<K, V> Shell<Result<(K, V)>> {
+    /// Collects key/value pairs into a `HashMap`, stopping at the first error.
+    ///
+    /// Later duplicates overwrite earlier values.
+    pub fn try_collect_map(self) -> Result<HashMap<K, V>>
+    where
+        K: Eq + Hash,
+    {
+        self.into_iter().collect()
+    }
+}
+
+impl<T: 'static> Shell<Result<T>> {
+    /// A single-item stream holding `result`.
+    pub fn from_result(result: Result<T>) -> Self {
+        Self::one(result)
+    }
+
+    /// A single-item stream holding `err`.
+    pub fn err_once(err: Error) -> Self {
+        Self::one(Err(err))
+    }
+
+    /// Yields `Ok` items until the first error, which is yielded before the stream ends.
+    pub fn take_while_ok(self) -> Self {
+        let mut iter = self.into_boxed();
+        let mut failed = false;
+        Shell::from_fn(move || {
+            if failed {
+                return None;
+            }
+            let item = iter.next()?;
+            failed = item.is_err();
+            Some(item)
+        })
+    }
+
+    /// Numbers the `Ok` items from zero; errors pass through without using up
+    /// an index.
+    pub fn enumerate_ok(self) -> Shell<Result<(usize, T)>> {
+        let iter = self.into_boxed();
+        let mut next = 0;
+        Shell::new(iter.map(move |item| {
+            item.map(|value| {
+                let index = next;
+                next += 1;
+                (index, value)
+            })
+        }))
+    }
+
+    /// Converts each error into a `T` with `f`, leaving a uniform `Shell<T>`.
+    pub fn unwrap_or_else_map<F>(self, mut f: F) -> Shell<T>
+    where
+        F: FnMut(Error) -> T + 'static,
+    {
+        let iter = self.into_boxed();
+        Shell::new(iter.map(move |item| item.unwrap_or_else(&mut f)))
+    }
+
+    /// Collects every `Ok` value, returning the first error instead if one occurs.
+    pub fn collect_results(self) -> Result<Vec<T>> {
+        self.into_boxed().collect()
+    }
+
+    /// Drains the whole stream, returning every value or, if anything failed,
+    /// every error in stream order.
+    pub fn collect_all(self) -> std::result::Result<Vec<T>, Vec<Error>> {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+        for item in self {
+            match item {
+                Ok(value) => values.push(value),
+                Err(err) => errors.push(err),
+            }
+        }
+        if errors.is_empty() {
+            Ok(values)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Shell<Result<String>> {
+    /// Streams lines from any buffered reader, stripping `\n` / `\r\n` like
+    /// [`read_lines`](crate::read_lines).
+    pub fn lines_from_reader<R>(reader: R) -> Self
+    where
+        R: BufRead + 'static,
+    {
+        Shell::new(reader.lines().map(|line| line.map_err(Into::into)))
+    }
+
+    /// Reassembles a line stream into text, terminating every line with `\n`.
+    ///
+    /// Stops at and returns the first error, e.g. from [`read_lines`](crate::read_lines).
+    pub fn into_text(self) -> Result<String> {
+        let mut text = String::new();
+        for line in self {
+            text.push_str(&line?);
+            text.push('\n');
+        }
+        Ok(text)
+    }
+}
+
 #[allow(dead_code)]
 impl<T: 'static> DoubleEndedShell<T> {
     /// Wraps any double-ended iterator.
@@ -366,6 +980,77 @@ impl<T: 'static> DoubleEndedShell<T> {
     pub fn into_shell(self) -> Shell<T> {
         Shell::new(self.iter)
     }
+
+    /// Yields the elements back-to-front.
+    pub fn rev(self) -> Shell<T> {
+        Shell::new(self.iter.rev())
+    }
+
+    /// Keeps only the last `n` elements, in their original order.
+    pub fn take_back(mut self, n: usize) -> Shell<T> {
+        let mut tail: Vec<T> = self.iter.by_ref().rev().take(n).collect();
+        tail.reverse();
+        Shell::new(tail.into_iter())
+    }
+}
+
+impl<T: 'static> PeekableShell<T> {
+    /// Returns a reference to the next element without consuming it.
+    ///
+    /// The first call advances the underlying iterator by one and buffers the item.
+    pub fn peek(&mut self) -> Option<&T> {
+        self.iter.peek()
+    }
+
+    /// Consumes and returns the next element only if it satisfies `predicate`.
+    pub fn next_if(&mut self, predicate: impl FnOnce(&T) -> bool) -> Option<T> {
+        self.iter.next_if(predicate)
+    }
+
+    /// Takes the leading items that satisfy `predicate`, leaving the first
+    /// failing item in place for [`PeekableShell::peek`] or `next`.
+    ///
+    /// Unlike [`Shell::take_while`], the boundary element is not consumed.
+    pub fn peeking_take_while(&mut self, mut predicate: impl FnMut(&T) -> bool) -> Vec<T> {
+        let mut taken = Vec::new();
+        while let Some(item) = self.iter.next_if(&mut predicate) {
+            taken.push(item);
+        }
+        taken
+    }
+
+    /// Converts back to a plain [`Shell`], keeping any peeked element.
+    pub fn into_shell(self) -> Shell<T> {
+        Shell::new(self.iter)
+    }
+}
+
+impl<T: 'static> ShellBuilder<T> {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// Adds a single item to the end.
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    /// Adds every item from `iter` to the end.
+    pub fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        self.items.extend(iter);
+    }
+
+    /// Returns the pushed items as a stream, in insertion order.
+    pub fn build(self) -> Shell<T> {
+        Shell::new(self.items.into_iter())
+    }
+}
+
+impl<T: 'static> Default for ShellBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> Iterator for Shell<T> {
@@ -384,6 +1069,14 @@ impl<T> Iterator for DoubleEndedShell<T> {
     }
 }
 
+impl<T> Iterator for PeekableShell<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
 impl<T> DoubleEndedIterator for DoubleEndedShell<T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back()
@@ -405,3 +1098,14 @@ impl<T: 'static> std::iter::FromIterator<T> for Shell<T> {
         Shell::new(data.into_iter())
     }
 }
+
+fn ensure_nonzero(size: usize, what: &str) -> Result<()> {
+    if size == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{what} must be greater than zero"),
+        )
+        .into());
+    }
+    Ok(())
+}
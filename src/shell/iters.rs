@@ -1,5 +1,6 @@
 use std::{
     collections::{HashSet, VecDeque},
+    iter::Peekable,
     sync::Arc,
     vec::IntoIter,
 };
@@ -38,6 +39,7 @@ pub struct WindowIter<T> {
     size: usize,
     buffer: VecDeque<T>,
     initialized: bool,
+    allow_partial: bool,
 }
 
 impl<T> WindowIter<T> {
@@ -47,6 +49,14 @@ impl<T> WindowIter<T> {
             size,
             buffer: VecDeque::new(),
             initialized: false,
+            allow_partial: false,
+        }
+    }
+
+    pub fn with_partial(iter: Box<dyn Iterator<Item = T> + 'static>, size: usize) -> Self {
+        Self {
+            allow_partial: true,
+            ..Self::new(iter, size)
         }
     }
 }
@@ -66,6 +76,9 @@ where
                 }
             }
             self.initialized = true;
+            if self.allow_partial && !self.buffer.is_empty() && self.buffer.len() < self.size {
+                return Some(self.buffer.drain(..).collect());
+            }
         }
         if self.buffer.len() < self.size {
             return None;
@@ -125,6 +138,39 @@ impl<T> Iterator for InterleaveIter<T> {
     }
 }
 
+pub struct IntersperseWithIter<T, F> {
+    iter: Peekable<Box<dyn Iterator<Item = T> + 'static>>,
+    separator: F,
+    needs_separator: bool,
+}
+
+impl<T, F> IntersperseWithIter<T, F> {
+    pub fn new(iter: Box<dyn Iterator<Item = T> + 'static>, separator: F) -> Self {
+        Self {
+            iter: iter.peekable(),
+            separator,
+            needs_separator: false,
+        }
+    }
+}
+
+impl<T, F> Iterator for IntersperseWithIter<T, F>
+where
+    F: FnMut() -> T,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.needs_separator && self.iter.peek().is_some() {
+            self.needs_separator = false;
+            return Some((self.separator)());
+        }
+        let item = self.iter.next()?;
+        self.needs_separator = true;
+        Some(item)
+    }
+}
+
 pub struct ProductIter<T, U> {
     base: T,
     others: Arc<Vec<U>>,
@@ -188,6 +234,87 @@ where
     }
 }
 
+pub struct DedupRecentIter<T> {
+    iter: Box<dyn Iterator<Item = T> + 'static>,
+    window: usize,
+    recent: VecDeque<T>,
+    seen: HashSet<T>,
+}
+
+impl<T> DedupRecentIter<T>
+where
+    T: Eq + std::hash::Hash,
+{
+    pub fn new(iter: Box<dyn Iterator<Item = T> + 'static>, window: usize) -> Self {
+        Self {
+            iter,
+            window,
+            recent: VecDeque::with_capacity(window),
+            seen: HashSet::with_capacity(window),
+        }
+    }
+}
+
+impl<T> Iterator for DedupRecentIter<T>
+where
+    T: Eq + std::hash::Hash + Clone,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window == 0 {
+            return self.iter.next();
+        }
+        let item = self.iter.by_ref().find(|item| !self.seen.contains(item))?;
+        if self.recent.len() == self.window
+            && let Some(evicted) = self.recent.pop_front()
+        {
+            self.seen.remove(&evicted);
+        }
+        self.recent.push_back(item.clone());
+        self.seen.insert(item.clone());
+        Some(item)
+    }
+}
+
+pub struct DedupByKeyIter<T, K, F> {
+    iter: Box<dyn Iterator<Item = T> + 'static>,
+    key_fn: F,
+    last: Option<K>,
+}
+
+impl<T, K, F> DedupByKeyIter<T, K, F>
+where
+    F: FnMut(&T) -> K,
+{
+    pub fn new(iter: Box<dyn Iterator<Item = T> + 'static>, key_fn: F) -> Self {
+        Self {
+            iter,
+            key_fn,
+            last: None,
+        }
+    }
+}
+
+impl<T, K, F> Iterator for DedupByKeyIter<T, K, F>
+where
+    K: PartialEq,
+    F: FnMut(&T) -> K,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for item in self.iter.by_ref() {
+            let key = (self.key_fn)(&item);
+            if self.last.as_ref() != Some(&key) {
+                self.last = Some(key);
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
 pub struct ChunkMapIter<T, U, F>
 where
     F: FnMut(Vec<T>) -> Vec<U>,
@@ -245,3 +372,70 @@ where
         }
     }
 }
+
+pub struct WithoutLastIter<T> {
+    iter: Box<dyn Iterator<Item = T> + 'static>,
+    pending: Option<T>,
+    primed: bool,
+}
+
+impl<T> WithoutLastIter<T> {
+    pub fn new(iter: Box<dyn Iterator<Item = T> + 'static>) -> Self {
+        Self {
+            iter,
+            pending: None,
+            primed: false,
+        }
+    }
+}
+
+impl<T> Iterator for WithoutLastIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.primed {
+            self.primed = true;
+            self.pending = self.iter.next();
+        }
+        let next = self.iter.next()?;
+        self.pending.replace(next)
+    }
+}
+
+pub struct ScanFramesIter<U, F> {
+    iter: Box<dyn Iterator<Item = Vec<u8>> + 'static>,
+    buffer: Vec<u8>,
+    extract: F,
+    _frame: std::marker::PhantomData<fn() -> U>,
+}
+
+impl<U, F> ScanFramesIter<U, F>
+where
+    F: FnMut(&mut Vec<u8>) -> Option<U>,
+{
+    pub fn new(iter: Box<dyn Iterator<Item = Vec<u8>> + 'static>, extract: F) -> Self {
+        Self {
+            iter,
+            buffer: Vec::new(),
+            extract,
+            _frame: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<U, F> Iterator for ScanFramesIter<U, F>
+where
+    F: FnMut(&mut Vec<u8>) -> Option<U>,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(frame) = (self.extract)(&mut self.buffer) {
+                return Some(frame);
+            }
+            let chunk = self.iter.next()?;
+            self.buffer.extend_from_slice(&chunk);
+        }
+    }
+}
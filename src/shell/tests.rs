@@ -1,4 +1,4 @@
-use super::{DoubleEndedShell, Shell};
+use super::{DoubleEndedShell, Shell, ShellBuilder};
 
 #[test]
 fn len_hint_tracks_iterator() {
@@ -80,6 +80,50 @@ fn chunk_map_parallel_transforms() {
     assert_eq!(values, vec![0, 2, 4, 6, 8, 10]);
 }
 
+#[cfg(feature = "parallel")]
+#[test]
+fn chunk_map_parallel_with_preserves_sequential_order() -> crate::Result<()> {
+    // Chunks expand to different lengths so reordering would be visible.
+    let expand = |chunk: Vec<usize>| -> Vec<usize> {
+        chunk.into_iter().flat_map(|n| vec![n; n % 3]).collect()
+    };
+    let sequential: Vec<_> = Shell::new(0..50).chunk_map(4, expand).collect();
+    let global: Vec<_> = Shell::new(0..50).chunk_map_parallel(4, expand).collect();
+    let scoped: Vec<_> = Shell::new(0..50)
+        .chunk_map_parallel_with(4, 2, expand)?
+        .collect();
+    assert_eq!(global, sequential);
+    assert_eq!(scoped, sequential);
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn par_map_matches_sequential_map() {
+    let parallel: Vec<_> = Shell::new(0..100).par_map(|n| n * n).collect();
+    let sequential: Vec<_> = Shell::new(0..100).map(|n| n * n).collect();
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn double_ended_shell_reverses_and_takes_back() {
+    assert_eq!(
+        DoubleEndedShell::from_vec(vec![1, 2, 3]).rev().to_vec(),
+        vec![3, 2, 1]
+    );
+    assert_eq!(
+        DoubleEndedShell::from_vec(vec![1, 2, 3, 4])
+            .take_back(2)
+            .to_vec(),
+        vec![3, 4]
+    );
+    let bottom_up = Shell::from_iter(["first", "second", "last"])
+        .into_double_ended()
+        .rev()
+        .to_vec();
+    assert_eq!(bottom_up, vec!["last", "second", "first"]);
+}
+
 #[test]
 fn double_ended_shell_pops_back() {
     let mut shell = DoubleEndedShell::from_vec(vec![1, 2, 3]);
@@ -87,3 +131,499 @@ fn double_ended_shell_pops_back() {
     assert_eq!(shell.next_back(), Some(3));
     assert_eq!(shell.into_shell().to_vec(), vec![2]);
 }
+
+#[test]
+fn windows_or_all_handles_short_streams() {
+    let full: Vec<_> = Shell::from_iter([1, 2, 3, 4]).windows_or_all(3).collect();
+    assert_eq!(full, vec![vec![1, 2, 3], vec![2, 3, 4]]);
+
+    let short: Vec<_> = Shell::from_iter([1, 2]).windows_or_all(5).collect();
+    assert_eq!(short, vec![vec![1, 2]]);
+
+    let empty: Vec<Vec<i32>> = Shell::from_iter(Vec::new()).windows_or_all(2).collect();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn windows_rc_shares_items_between_windows() {
+    let windows: Vec<_> = Shell::from_iter(["a", "b", "c", "d"].map(String::from))
+        .windows_rc(3)
+        .collect();
+    let contents: Vec<Vec<&str>> = windows
+        .iter()
+        .map(|window| window.iter().map(|item| item.as_str()).collect())
+        .collect();
+    assert_eq!(contents, vec![vec!["a", "b", "c"], vec!["b", "c", "d"]]);
+    assert!(std::rc::Rc::ptr_eq(&windows[0][1], &windows[1][0]));
+    assert_eq!(std::rc::Rc::strong_count(&windows[0][2]), 2);
+    assert_eq!(std::rc::Rc::strong_count(&windows[0][0]), 1);
+}
+
+#[test]
+fn prepend_and_append_single_items() {
+    let items: Vec<_> = Shell::from_iter([2, 3]).prepend(1).append(4).collect();
+    assert_eq!(items, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn pairwise_yields_adjacent_pairs() {
+    let pairs: Vec<_> = Shell::from_iter([1, 4, 9]).pairwise().collect();
+    assert_eq!(pairs, vec![(1, 4), (4, 9)]);
+
+    let single: Vec<(i32, i32)> = Shell::from_iter([7]).pairwise().collect();
+    assert!(single.is_empty());
+}
+
+#[test]
+fn collect_btreemap_sorts_keys() {
+    let map = Shell::from_iter([("b", 2), ("a", 1), ("c", 3), ("a", 4)]).collect_btreemap();
+    assert_eq!(
+        map.into_iter().collect::<Vec<_>>(),
+        vec![("a", 4), ("b", 2), ("c", 3)]
+    );
+}
+
+#[test]
+fn group_into_map_groups_entries_by_extension() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    for name in ["a.rs", "b.txt", "c.rs", "d"] {
+        crate::write_text(dir.path().join(name), "x")?;
+    }
+    let entries = crate::ls_detailed(dir.path())?.collect::<crate::Result<Vec<_>>>()?;
+    let groups = Shell::from_iter(entries).group_into_map(|entry| {
+        entry
+            .extension()
+            .map(|ext| ext.to_string_lossy().into_owned())
+    });
+    assert_eq!(groups[&Some("rs".to_string())].len(), 2);
+    assert_eq!(groups[&Some("txt".to_string())].len(), 1);
+    assert_eq!(groups[&None].len(), 1);
+    Ok(())
+}
+
+#[test]
+fn into_text_joins_lines_and_short_circuits() {
+    let lines = Shell::from_iter(["a", "b"].map(|line| Ok(line.to_string())));
+    assert_eq!(lines.into_text().unwrap(), "a\nb\n");
+
+    let broken = Shell::from_iter([
+        Ok("a".to_string()),
+        Err(crate::Error::Io(std::io::Error::other("read failed"))),
+        Ok("c".to_string()),
+    ]);
+    assert!(matches!(broken.into_text(), Err(crate::Error::Io(_))));
+}
+
+#[test]
+fn from_result_and_err_once_yield_one_item() {
+    let ok: Vec<_> = Shell::from_result(Ok(7)).collect();
+    assert_eq!(ok.len(), 1);
+    assert!(matches!(ok[0], Ok(7)));
+
+    let err: Vec<crate::Result<i32>> =
+        Shell::err_once(crate::Error::Io(std::io::Error::other("nope"))).collect();
+    assert_eq!(err.len(), 1);
+    assert!(err[0].is_err());
+}
+
+#[test]
+fn take_while_ok_stops_after_first_error() {
+    let items: Vec<_> = Shell::from_iter([
+        Ok(1),
+        Ok(2),
+        Err(crate::Error::Io(std::io::Error::other("boom"))),
+        Ok(3),
+    ])
+    .take_while_ok()
+    .collect();
+    assert_eq!(items.len(), 3);
+    assert!(matches!(items[0], Ok(1)));
+    assert!(matches!(items[1], Ok(2)));
+    assert!(items[2].is_err());
+}
+
+#[test]
+fn reduce_seeds_with_first_element() {
+    assert_eq!(
+        Shell::from_iter(Vec::<usize>::new()).reduce(usize::max),
+        None
+    );
+    assert_eq!(Shell::one(4).reduce(|a, b| a + b), Some(4));
+    let longest = Shell::from_iter(["a", "abc", "ab"])
+        .map(str::len)
+        .reduce(usize::max);
+    assert_eq!(longest, Some(3));
+}
+
+#[test]
+fn group_by_preserves_bucket_order() {
+    let groups = Shell::from_iter(["src/a.rs", "docs/x.md", "src/b.rs", "docs/y.md", "src/c.rs"])
+        .group_by(|path| path.split('/').next().unwrap_or_default().to_string());
+    assert_eq!(groups["src"], vec!["src/a.rs", "src/b.rs", "src/c.rs"]);
+    assert_eq!(groups["docs"], vec!["docs/x.md", "docs/y.md"]);
+}
+
+#[test]
+fn dedup_drops_only_adjacent_duplicates() {
+    let deduped: Vec<_> = Shell::from_iter([1, 1, 2, 2, 2, 1, 3, 3]).dedup().collect();
+    assert_eq!(deduped, vec![1, 2, 1, 3]);
+
+    let by_key: Vec<_> = Shell::from_iter(["apple", "avocado", "banana", "blueberry", "apricot"])
+        .dedup_by_key(|word| word.chars().next())
+        .collect();
+    assert_eq!(by_key, vec!["apple", "banana", "apricot"]);
+}
+
+#[test]
+fn intersperse_with_generates_separators_lazily() {
+    let mut calls = 0;
+    let values: Vec<_> = Shell::from_iter(["a", "b", "c"].map(String::from))
+        .intersperse_with(move || {
+            calls += 1;
+            format!("<{calls}>")
+        })
+        .collect();
+    assert_eq!(values, vec!["a", "<1>", "b", "<2>", "c"]);
+
+    let single: Vec<_> = Shell::one(1).intersperse_with(|| unreachable!()).collect();
+    assert_eq!(single, vec![1]);
+}
+
+#[test]
+fn partition_routes_items_into_both_collections() {
+    let (evens, odds): (Vec<_>, Vec<_>) = Shell::new(0..10).partition(|n| n % 2 == 0);
+    assert_eq!(evens, vec![0, 2, 4, 6, 8]);
+    assert_eq!(odds, vec![1, 3, 5, 7, 9]);
+}
+
+#[test]
+fn scan_produces_running_totals_until_none() {
+    let totals: Vec<_> = Shell::from_iter([10, 20, 30, 40])
+        .scan(0, |sum, size| {
+            *sum += size;
+            (*sum <= 60).then_some(*sum)
+        })
+        .collect();
+    assert_eq!(totals, vec![10, 30, 60]);
+}
+
+#[test]
+fn without_last_drops_only_the_final_item() {
+    let values: Vec<_> = Shell::from_iter([1, 2, 3]).without_last().collect();
+    assert_eq!(values, vec![1, 2]);
+    assert!(Shell::<i32>::empty().without_last().to_vec().is_empty());
+    assert!(Shell::one(1).without_last().to_vec().is_empty());
+}
+
+#[test]
+fn dedup_recent_only_remembers_the_window() {
+    let values: Vec<_> = Shell::from_iter(["a", "b", "a", "c", "d", "a"])
+        .dedup_recent(2)
+        .collect();
+    assert_eq!(values, vec!["a", "b", "c", "d", "a"]);
+}
+
+#[test]
+fn peekable_looks_ahead_without_consuming() {
+    let mut lines = Shell::from_iter(["[core]", "a = 1", "b = 2", "[user]", "name = x"]).peekable();
+    assert_eq!(lines.peek(), Some(&"[core]"));
+    assert_eq!(lines.next(), Some("[core]"));
+
+    let mut section = Vec::new();
+    while let Some(line) = lines.next_if(|line| !line.starts_with('[')) {
+        section.push(line);
+    }
+    assert_eq!(section, vec!["a = 1", "b = 2"]);
+    assert_eq!(lines.into_shell().to_vec(), vec!["[user]", "name = x"]);
+}
+
+#[test]
+fn try_fold_and_try_for_each_short_circuit() {
+    let sum = Shell::from_iter([1, 2, 3]).try_fold(0, |acc, n| Ok::<_, String>(acc + n));
+    assert_eq!(sum, Ok(6));
+
+    let mut seen = Vec::new();
+    let result = Shell::from_iter([1, 2, 3, 4]).try_for_each(|n| {
+        if n == 3 {
+            return Err(format!("bad {n}"));
+        }
+        seen.push(n);
+        Ok(())
+    });
+    assert_eq!(result, Err("bad 3".to_string()));
+    assert_eq!(seen, vec![1, 2]);
+}
+
+#[test]
+fn try_fold_and_try_for_each_leave_the_rest_of_the_stream() {
+    let mut shell = Shell::new(1..=6);
+    let partial = shell.try_fold(0, |acc, n| if n < 3 { Ok(acc + n) } else { Err(acc) });
+    assert_eq!(partial, Err(3));
+    assert_eq!(shell.next(), Some(4));
+
+    let result = shell.try_for_each(|n| if n == 5 { Err(n) } else { Ok(()) });
+    assert_eq!(result, Err(5));
+    assert_eq!(shell.collect::<Vec<_>>(), vec![6]);
+}
+
+#[test]
+fn collect_results_returns_first_error() -> crate::Result<()> {
+    let values = Shell::from_iter([Ok(1), Ok(2)]).collect_results()?;
+    assert_eq!(values, vec![1, 2]);
+
+    let err = Shell::from_iter([
+        Ok(1),
+        Err(crate::Error::Io(std::io::Error::other("boom"))),
+        Ok(3),
+    ])
+    .collect_results()
+    .unwrap_err();
+    assert!(err.to_string().contains("boom"));
+    Ok(())
+}
+
+#[test]
+fn collect_all_reports_every_error() {
+    let values = Shell::from_iter([Ok(1), Ok(2)]).collect_all().unwrap();
+    assert_eq!(values, vec![1, 2]);
+
+    let errors = Shell::from_iter([
+        Err(crate::Error::Io(std::io::Error::other("first"))),
+        Ok(2),
+        Err(crate::Error::Io(std::io::Error::other("second"))),
+    ])
+    .collect_all()
+    .unwrap_err();
+    let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].contains("first"));
+    assert!(messages[1].contains("second"));
+}
+
+#[test]
+fn unwrap_or_else_map_replaces_errors() {
+    let lines: Vec<_> = Shell::from_iter([
+        Ok("one".to_string()),
+        Err(crate::Error::Io(std::io::Error::other("boom"))),
+        Ok("three".to_string()),
+    ])
+    .unwrap_or_else_map(|_| "<error>".to_string())
+    .collect();
+    assert_eq!(lines, vec!["one", "<error>", "three"]);
+}
+
+#[test]
+fn try_chunking_rejects_zero_sizes() -> crate::Result<()> {
+    assert!(Shell::new(1..=3).try_chunks(0).is_err());
+    assert!(Shell::new(1..=3).try_windows(0).is_err());
+    assert!(Shell::new(1..=3).try_chunk_map(0, |chunk| chunk).is_err());
+
+    let chunks: Vec<_> = Shell::new(1..=3).try_chunks(2)?.collect();
+    assert_eq!(chunks, vec![vec![1, 2], vec![3]]);
+    let windows: Vec<_> = Shell::new(1..=3).try_windows(2)?.collect();
+    assert_eq!(windows, vec![vec![1, 2], vec![2, 3]]);
+    let doubled: Vec<_> = Shell::new(1..=3)
+        .try_chunk_map(2, |chunk| chunk.into_iter().map(|n| n * 2).collect())?
+        .collect();
+    assert_eq!(doubled, vec![2, 4, 6]);
+    Ok(())
+}
+
+#[test]
+fn enumerate_ok_skips_errors() {
+    let items: Vec<_> = Shell::from_iter([
+        Ok("a"),
+        Err(crate::Error::Io(std::io::Error::other("skip"))),
+        Ok("b"),
+        Ok("c"),
+    ])
+    .enumerate_ok()
+    .collect();
+    assert!(items[1].is_err());
+    let numbered: Vec<_> = items.into_iter().filter_map(|item| item.ok()).collect();
+    assert_eq!(numbered, vec![(0, "a"), (1, "b"), (2, "c")]);
+}
+
+#[test]
+fn flatten_round_trips_chunks() {
+    let values: Vec<_> = Shell::new(1..=7).chunks(3).flatten().collect();
+    assert_eq!(values, (1..=7).collect::<Vec<_>>());
+
+    let nested: Vec<_> = Shell::from_iter([Some(1), None, Some(3)])
+        .flatten()
+        .collect();
+    assert_eq!(nested, vec![1, 3]);
+}
+
+#[test]
+fn try_collect_map_builds_config_or_fails() {
+    fn parse(line: &str) -> crate::Result<(String, String)> {
+        line.split_once('=')
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .ok_or_else(|| crate::Error::Io(std::io::Error::other(format!("bad line: {line}"))))
+    }
+
+    let config = Shell::from_iter(["HOST = localhost", "PORT=8080"])
+        .map(parse)
+        .try_collect_map()
+        .unwrap();
+    assert_eq!(config["HOST"], "localhost");
+    assert_eq!(config["PORT"], "8080");
+
+    let err = Shell::from_iter(["HOST=a", "garbage", "PORT=1"])
+        .map(parse)
+        .try_collect_map()
+        .unwrap_err();
+    assert!(err.to_string().contains("bad line: garbage"));
+}
+
+#[test]
+fn terminal_helpers_read_fluently() {
+    assert_eq!(Shell::new(1..=4).count(), 4);
+    assert_eq!(Shell::new(1..=4).last(), Some(4));
+    assert_eq!(Shell::new(1..=4).nth(1), Some(2));
+    assert_eq!(Shell::new(1..=4).nth(9), None);
+    assert_eq!(Shell::new(1..=4).sum::<i32>(), 10);
+    assert_eq!(Shell::from_iter([3, 9, 1]).max(), Some(9));
+    assert_eq!(Shell::from_iter([3, 9, 1]).min(), Some(1));
+    assert_eq!(Shell::<i32>::empty().max(), None);
+}
+
+#[test]
+fn nth_leaves_the_rest_of_the_stream() {
+    let mut shell = Shell::new(1..=5);
+    assert_eq!(shell.nth(1), Some(2));
+    assert_eq!(shell.next(), Some(3));
+    assert_eq!(shell.collect::<Vec<_>>(), vec![4, 5]);
+}
+
+#[test]
+fn sorted_by_orders_entries_by_size() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    for (name, body) in [("small", "x"), ("large", "xxxxx"), ("medium", "xxx")] {
+        crate::write_text(dir.path().join(name), body)?;
+    }
+    let names = |shell: Shell<crate::PathEntry>| -> Vec<String> {
+        shell
+            .filter_map(|entry| Some(entry.file_name()?.to_string_lossy().into_owned()))
+            .collect()
+    };
+
+    let entries = crate::ls_detailed(dir.path())?.collect_results()?;
+    let descending = Shell::from_iter(entries.clone()).sorted_by(|a, b| b.size().cmp(&a.size()));
+    assert_eq!(names(descending), vec!["large", "medium", "small"]);
+
+    let ascending = Shell::from_iter(entries).sorted_by_key(|entry| entry.size());
+    assert_eq!(names(ascending), vec!["small", "medium", "large"]);
+    Ok(())
+}
+
+#[test]
+fn peeking_take_while_keeps_the_boundary() {
+    let mut chars = Shell::from_iter("123abc".chars()).peekable();
+    let digits: String = chars
+        .peeking_take_while(char::is_ascii_digit)
+        .into_iter()
+        .collect();
+    assert_eq!(digits, "123");
+    assert_eq!(chars.peek(), Some(&'a'));
+    assert_eq!(chars.into_shell().join(""), "abc");
+}
+
+#[test]
+fn unzip_splits_pairs_into_columns() {
+    let (indices, words): (Vec<_>, Vec<_>) = Shell::from_iter(["a", "b", "c"]).enumerate().unzip();
+    assert_eq!(indices, vec![0, 1, 2]);
+    assert_eq!(words, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn lines_from_reader_trims_line_endings() -> crate::Result<()> {
+    let reader = std::io::Cursor::new(b"alpha\r\nbeta\ngamma".as_slice());
+    let lines = Shell::lines_from_reader(reader).collect_results()?;
+    assert_eq!(lines, vec!["alpha", "beta", "gamma"]);
+    Ok(())
+}
+
+#[test]
+fn average_and_variance_reduce_numeric_streams() {
+    assert_eq!(Shell::from_iter([1, 2, 3, 4]).average(), Some(2.5));
+    assert_eq!(Shell::from_iter([1, 2, 3, 4]).variance(), Some(1.25));
+    let variance = Shell::from_iter([2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0])
+        .variance()
+        .unwrap();
+    assert!((variance - 4.0).abs() < 1e-12);
+    assert_eq!(Shell::<u8>::empty().average(), None);
+    assert_eq!(Shell::<u8>::empty().variance(), None);
+}
+
+#[test]
+fn cycle_take_repeats_a_bounded_number_of_items() {
+    assert_eq!(
+        Shell::from_iter([1, 2]).cycle_take(5).to_vec(),
+        vec![1, 2, 1, 2, 1]
+    );
+    assert!(Shell::<i32>::empty().cycle_take(5).to_vec().is_empty());
+    assert!(Shell::from_iter([1, 2]).cycle_take(0).to_vec().is_empty());
+}
+
+#[test]
+fn collect_vec_matches_to_vec() {
+    let collected = Shell::new(0..1000).collect_vec();
+    assert!(collected.capacity() >= 1000);
+    assert_eq!(collected, Shell::new(0..1000).to_vec());
+}
+
+#[test]
+fn flat_map_shell_expands_roots_into_walks() -> crate::Result<()> {
+    let first = tempfile::tempdir()?;
+    let second = tempfile::tempdir()?;
+    crate::write_text(first.path().join("a.txt"), "a")?;
+    crate::write_text(second.path().join("b.txt"), "b")?;
+    crate::write_text(second.path().join("c.txt"), "c")?;
+
+    let roots = vec![first.path().to_path_buf(), second.path().to_path_buf()];
+    let files = Shell::from_iter(roots)
+        .flat_map_shell(|root| crate::walk_files(root).unwrap_or_else(Shell::err_once))
+        .collect_results()?;
+    assert_eq!(files.len(), 3);
+    assert!(files[0].path.starts_with(first.path()));
+    Ok(())
+}
+
+#[test]
+fn scan_frames_reassembles_length_prefixed_frames() {
+    let chunks = vec![
+        vec![3, b'a'],
+        vec![b'b', b'c', 0, 2],
+        vec![b'x'],
+        vec![b'y', 5, b'z'],
+    ];
+    let frames: Vec<Vec<u8>> = Shell::from_iter(chunks)
+        .scan_frames(|buffer| {
+            let len = usize::from(*buffer.first()?);
+            if buffer.len() < len + 1 {
+                return None;
+            }
+            let frame = buffer[1..=len].to_vec();
+            buffer.drain(..=len);
+            Some(frame)
+        })
+        .collect();
+    assert_eq!(frames, vec![b"abc".to_vec(), Vec::new(), b"xy".to_vec()]);
+}
+
+#[test]
+fn shell_builder_keeps_insertion_order() {
+    let mut builder = ShellBuilder::new();
+    for n in [10, 20, 30] {
+        if n > 25 {
+            break;
+        }
+        builder.push(n);
+    }
+    builder.extend(1..4);
+    builder.push(99);
+    let items: Vec<_> = builder.build().collect();
+    assert_eq!(items, vec![10, 20, 1, 2, 3, 99]);
+}
@@ -1,4 +1,9 @@
-use std::{fs, thread, time::Duration};
+use std::{
+    fs,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
 
 use qshr::{Error, prelude::*};
 use tempfile::tempdir;
@@ -21,6 +26,42 @@ fn next_event(
     Err(Error::Io(std::io::Error::other("watch event timeout")))
 }
 
+/// Drains the stream built by `make` on a helper thread so callers can bound
+/// every wait with `recv_timeout` instead of blocking on the watcher forever.
+fn spawn_stream<T, F>(make: F) -> qshr::Result<mpsc::Receiver<qshr::Result<T>>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> qshr::Result<Shell<qshr::Result<T>>> + Send + 'static,
+{
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let stream = match make() {
+            Ok(stream) => stream,
+            Err(err) => {
+                let _ = ready_tx.send(Err(err));
+                return;
+            }
+        };
+        let _ = ready_tx.send(Ok(()));
+        for item in stream {
+            if tx.send(item).is_err() {
+                break;
+            }
+        }
+    });
+    ready_rx
+        .recv()
+        .map_err(|_| Error::Io(std::io::Error::other("watch thread exited")))??;
+    Ok(rx)
+}
+
+fn recv_before<T>(rx: &mpsc::Receiver<qshr::Result<T>>, deadline: Instant) -> qshr::Result<T> {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    rx.recv_timeout(remaining)
+        .map_err(|_| Error::Io(std::io::Error::other("watch event timeout")))?
+}
+
 #[test]
 fn watch_filtered_and_glob_emit_expected_events() -> qshr::Result<()> {
     let dir = tempdir()?;
@@ -46,6 +87,35 @@ fn watch_filtered_and_glob_emit_expected_events() -> qshr::Result<()> {
     Ok(())
 }
 
+#[test]
+fn watch_paths_yields_matching_entries() -> qshr::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path().to_path_buf();
+    let file = root.join("rebuild.rs");
+    let paths = spawn_stream({
+        let root = root.clone();
+        move || watch_paths(&root, Duration::from_millis(100), "**/*.rs")
+    })?;
+
+    thread::spawn({
+        let file = file.clone();
+        move || {
+            thread::sleep(Duration::from_millis(50));
+            let _ = write_text(root.join("ignored.txt"), "skip");
+            let _ = write_text(&file, "fn main() {}");
+        }
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    loop {
+        let entry = recv_before(&paths, deadline)?;
+        assert_ne!(entry.extension(), Some("txt".as_ref()));
+        if entry.path == file {
+            return Ok(());
+        }
+    }
+}
+
 #[test]
 fn watch_channel_reports_renames() -> qshr::Result<()> {
     let dir = tempdir()?;
@@ -96,3 +166,43 @@ fn debounce_watch_suppresses_duplicate_events() -> qshr::Result<()> {
     assert_eq!(deduped.len(), 1);
     Ok(())
 }
+
+#[test]
+fn watch_coalesced_merges_rapid_writes() -> qshr::Result<()> {
+    let dir = tempdir()?;
+    let file = dir.path().join("burst.txt");
+    let sentinel = dir.path().join("sentinel.txt");
+    write_text(&file, "seed")?;
+    let events = spawn_stream({
+        let root = dir.path().to_path_buf();
+        move || watch_coalesced(root, Duration::from_millis(300))
+    })?;
+
+    thread::spawn({
+        let file = file.clone();
+        let sentinel = sentinel.clone();
+        move || {
+            thread::sleep(Duration::from_millis(50));
+            for n in 0..3 {
+                let _ = write_text(&file, format!("write {n}"));
+            }
+            thread::sleep(Duration::from_millis(800));
+            let _ = write_text(&sentinel, "done");
+        }
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut burst = Vec::new();
+    loop {
+        let event = recv_before(&events, deadline)?;
+        if event.path() == sentinel {
+            break;
+        }
+        if event.path() == file {
+            burst.push(event);
+        }
+    }
+    assert_eq!(burst.len(), 1, "{burst:?}");
+    assert!(matches!(burst[0], WatchEvent::Modified(_)));
+    Ok(())
+}